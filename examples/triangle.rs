@@ -188,13 +188,13 @@ async fn run(effect: EffectType, enable_effect: bool, event_loop: EventLoop<()>,
                     rpass.draw(0..3, 0..1);
                 }
 
-                queue.submit(Some(encoder.finish()));
-
                 if let Some(frame) = process_frame {
                     // Resolve the post-processing on the frame after rendering the triangle.
-                    frame.resolve();
+                    frame.resolve_with_encoder(&mut encoder);
                 }
 
+                queue.submit(Some(encoder.finish()));
+
                 // Finally present the post-processed frame.
                 frame.present();
             }
@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+
+/// The maximum number of bind groups which are kept by a [BindGroupCache].
+///
+/// Views which are recreated every frame (e.g. views of the swapchain texture)
+/// would otherwise grow the cache indefinitely.
+const CAPACITY: usize = 8;
+
+/// Caches bind groups which have been created for a specific input texture view.
+///
+/// Bind groups which depend on several views are cached using a key which
+/// combines their ids, e.g. `[wgpu::Id; 2]`.
+pub(crate) struct BindGroupCache<K = wgpu::Id> {
+    entries: VecDeque<(K, wgpu::BindGroup)>,
+}
+
+impl<K: PartialEq> BindGroupCache<K> {
+    /// Creates a new, empty [BindGroupCache].
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: VecDeque::with_capacity(CAPACITY),
+        }
+    }
+
+    /// Returns the bind group which belongs to `key`.
+    ///
+    /// If there is no such bind group yet, it is created using `create`
+    /// and the oldest bind group is evicted if the cache is full.
+    ///
+    /// Arguments:
+    ///
+    /// * `key`: The key which identifies the resources of the bind group.
+    /// * `create`: Creates the bind group if it is not cached yet.
+    ///
+    /// Returns:
+    ///
+    /// The cached bind group.
+    pub(crate) fn get_or_insert_with_key(
+        &mut self,
+        key: K,
+        create: impl FnOnce() -> wgpu::BindGroup,
+    ) -> &wgpu::BindGroup {
+        let index = match self.entries.iter().position(|(cached, _)| *cached == key) {
+            Some(index) => index,
+            None => {
+                if self.entries.len() == CAPACITY {
+                    self.entries.pop_front();
+                }
+                self.entries.push_back((key, create()));
+                self.entries.len() - 1
+            }
+        };
+
+        &self.entries[index].1
+    }
+
+    /// Removes all cached bind groups.
+    ///
+    /// This should be called whenever the cached bind groups might
+    /// reference resources which are no longer used.
+    #[cfg(any(
+        feature = "bloom",
+        feature = "blur",
+        feature = "dof",
+        feature = "fxaa",
+        feature = "grayscale",
+        feature = "lut",
+        feature = "smaa",
+        feature = "taa",
+        feature = "tonemap"
+    ))]
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl BindGroupCache {
+    /// Returns the bind group which belongs to `view`.
+    ///
+    /// If there is no such bind group yet, it is created using `create`
+    /// and the oldest bind group is evicted if the cache is full.
+    ///
+    /// Arguments:
+    ///
+    /// * `view`: The texture view for which the bind group was created.
+    /// * `create`: Creates the bind group if it is not cached yet.
+    ///
+    /// Returns:
+    ///
+    /// The cached bind group.
+    pub(crate) fn get_or_insert_with(
+        &mut self,
+        view: &wgpu::TextureView,
+        create: impl FnOnce() -> wgpu::BindGroup,
+    ) -> &wgpu::BindGroup {
+        self.get_or_insert_with_key(view.global_id(), create)
+    }
+}
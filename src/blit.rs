@@ -0,0 +1,223 @@
+use crate::{
+    bind_group_cache::BindGroupCache,
+    shader::{include_composed_wgsl, VertexShader},
+};
+
+/// The entries of the bind group layout, which match the bindings of `shader/blit.wgsl`.
+const BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 1] = [wgpu::BindGroupLayoutEntry {
+    binding: 0,
+    visibility: wgpu::ShaderStages::FRAGMENT,
+    ty: wgpu::BindingType::Texture {
+        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+        view_dimension: wgpu::TextureViewDimension::D2,
+        multisampled: false,
+    },
+    count: None,
+}];
+
+/// Copies a frame unchanged into a view of a possibly different format.
+///
+/// A texture copy requires both textures to share a format and size, while the
+/// input of an effect often uses an intermediate format which differs from the output.
+pub(crate) struct Blit {
+    pipeline: wgpu::RenderPipeline,
+    _vertex_shader: VertexShader,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_groups: BindGroupCache,
+}
+
+impl Blit {
+    /// Creates a new [Blit].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `format`: The texture format of the views into which the frames are copied.
+    ///
+    /// Returns:
+    ///
+    /// The new [Blit].
+    pub(crate) fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        Self::with_sample_count(device, format, 1)
+    }
+
+    /// Creates a new [Blit] which copies frames into multisampled views.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `format`: The texture format of the views into which the frames are copied.
+    /// * `sample_count`: The number of samples per pixel of these views.
+    ///
+    /// Returns:
+    ///
+    /// The new [Blit].
+    pub(crate) fn with_sample_count(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &BIND_GROUP_LAYOUT_ENTRIES,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let blit_shader = include_composed_wgsl!(device, "shader/blit.wgsl");
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: vertex_shader.state(),
+            fragment: Some(wgpu::FragmentState {
+                module: &blit_shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent::REPLACE,
+                        alpha: wgpu::BlendComponent::REPLACE,
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: Default::default(),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            _vertex_shader: vertex_shader,
+            bind_group_layout,
+            bind_groups: BindGroupCache::new(),
+        }
+    }
+
+    /// Records a pass which copies `input_view` into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the pass is recorded.
+    /// * `input_view`: The view which is copied.
+    /// * `output_view`: The view into which the copy is written.
+    pub(crate) fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        output_view: &wgpu::TextureView,
+    ) {
+        let attachment = wgpu::RenderPassColorAttachment {
+            view: output_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: true,
+            },
+        };
+        self.draw(device, encoder, input_view, attachment);
+    }
+
+    /// Records a pass which copies `input_view` into an arbitrary color attachment,
+    /// e.g. into the multisampled view of a frame.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the pass is recorded.
+    /// * `input_view`: The view which is copied.
+    /// * `attachment`: The attachment into which the copy is written.
+    pub(crate) fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        attachment: wgpu::RenderPassColorAttachment<'_>,
+    ) {
+        let bind_group = self.bind_groups.get_or_insert_with(input_view, || {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(input_view),
+                }],
+                layout: &self.bind_group_layout,
+            })
+        });
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[Some(attachment)],
+            depth_stencil_attachment: None,
+            label: None,
+        });
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+/// A [Blit] which is created once it is needed and recreated
+/// if the format or sample count of its views changes.
+#[derive(Default)]
+pub struct LazyBlit {
+    blit: Option<(wgpu::TextureFormat, u32, Blit)>,
+}
+
+impl LazyBlit {
+    /// Returns the [Blit] which copies frames into views of `format` and `sample_count`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `format`: The texture format of the views into which the frames are copied.
+    /// * `sample_count`: The number of samples per pixel of these views.
+    ///
+    /// Returns:
+    ///
+    /// The existing or new [Blit].
+    pub(crate) fn get(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> &mut Blit {
+        if !matches!(self.blit, Some((f, s, _)) if f == format && s == sample_count) {
+            self.blit = Some((
+                format,
+                sample_count,
+                Blit::with_sample_count(device, format, sample_count),
+            ));
+        }
+
+        &mut self.blit.as_mut().unwrap().2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shader_matches_bind_group_layout() {
+        validation::check_entry_point(
+            "shader/blit.wgsl",
+            include_str!("shader/blit.wgsl"),
+            naga::ShaderStage::Fragment,
+            "fs_main",
+            &[&BIND_GROUP_LAYOUT_ENTRIES],
+        );
+    }
+}
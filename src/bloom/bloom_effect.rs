@@ -0,0 +1,36 @@
+use crate::{
+    effect_state::{impl_effect, EffectState},
+    EffectFrame,
+};
+
+use super::{bloom_effect_inner::BloomEffectInner, BloomSettings};
+
+/// Adds a glow around bright areas of the frame.
+///
+/// The bright parts of the frame are downsampled through a chain of mip levels
+/// using a 13 tap filter and upsampled again using a tent filter, which results
+/// in a wide and stable bloom that is added onto the frame.
+/// The frames of this effect are `Rgba16Float` by default, independent of the
+/// format of the output, so that values above the threshold are preserved.
+/// Another format can be chosen using an [crate::EffectFormat].
+pub struct BloomEffect {
+    pub(super) state: EffectState<BloomEffectInner>,
+    pub(super) settings: BloomSettings,
+}
+
+/// The frame into which the scene is rendered before the [BloomEffect] is resolved.
+pub type BloomFrame<'a> = EffectFrame<'a, BloomEffect>;
+
+impl BloomEffect {
+    /// Creates the resources of the effect using the current size, format and settings.
+    fn create_inner(&self, device: &wgpu::Device) -> BloomEffectInner {
+        BloomEffectInner::new(
+            device,
+            &self.state.allocation_size(),
+            self.state.format(),
+            &self.settings,
+        )
+    }
+}
+
+impl_effect!(BloomEffect, "bloom", BloomSettings, hdr);
@@ -0,0 +1,546 @@
+use std::num::NonZeroU32;
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::BloomSettings;
+
+/// The format of the mip chain, which keeps the range of HDR inputs.
+const MIP_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// The entries of the layout of the source bind group, which is shared by all passes
+/// and matches group 0 of `shader/bloom.wgsl`.
+const SOURCE_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 3] = [
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 2,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(BloomSettings::SIZE as u64),
+        },
+        count: None,
+    },
+];
+
+/// The entries of the layout of the scene bind group, which matches group 1 of `shader/bloom.wgsl`.
+const SCENE_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 1] =
+    [wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }];
+
+/// Wraps the internal fields and implementation
+/// of a [crate::bloom::BloomEffect].
+pub(super) struct BloomEffectInner {
+    pub(super) prefilter_pipeline: wgpu::RenderPipeline,
+    pub(super) downsample_pipeline: wgpu::RenderPipeline,
+    pub(super) upsample_pipeline: wgpu::RenderPipeline,
+    pub(super) composite_pipeline: wgpu::RenderPipeline,
+    pub(super) _vertex_shader: VertexShader,
+    pub(super) source_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) scene_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) sampler: wgpu::Sampler,
+    pub(super) settings: BloomSettings,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) mips: Vec<wgpu::TextureView>,
+    pub(super) mip_bind_groups: Vec<wgpu::BindGroup>,
+    pub(super) prefilter_bind_groups: BindGroupCache,
+    pub(super) scene_bind_groups: BindGroupCache,
+    pub(super) size: wgpu::Extent3d,
+}
+
+impl BloomEffectInner {
+    /// Creates a new [BloomEffectInner].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The size of the frame which will later be processed.
+    /// * `format`: The texture format of the post-processed frame.
+    /// * `settings`: The settings of the bloom.
+    ///
+    /// Returns:
+    ///
+    /// The new [BloomEffectInner].
+    pub(super) fn new(
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+        format: EffectFormat,
+        settings: &BloomSettings,
+    ) -> Self {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &settings.to_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let source_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &SOURCE_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let scene_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &SCENE_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let bloom_shader = include_composed_wgsl!(device, "shader/bloom.wgsl");
+
+        let replace = wgpu::BlendState {
+            color: wgpu::BlendComponent::REPLACE,
+            alpha: wgpu::BlendComponent::REPLACE,
+        };
+        let additive = wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent::REPLACE,
+        };
+
+        let prefilter_pipeline = create_pipeline(
+            device,
+            &[&source_bind_group_layout],
+            &vertex_shader,
+            &bloom_shader,
+            "fs_prefilter",
+            MIP_FORMAT,
+            replace,
+        );
+        let downsample_pipeline = create_pipeline(
+            device,
+            &[&source_bind_group_layout],
+            &vertex_shader,
+            &bloom_shader,
+            "fs_downsample",
+            MIP_FORMAT,
+            replace,
+        );
+        let upsample_pipeline = create_pipeline(
+            device,
+            &[&source_bind_group_layout],
+            &vertex_shader,
+            &bloom_shader,
+            "fs_upsample",
+            MIP_FORMAT,
+            additive,
+        );
+        let composite_pipeline = create_pipeline(
+            device,
+            &[&source_bind_group_layout, &scene_bind_group_layout],
+            &vertex_shader,
+            &bloom_shader,
+            "fs_composite",
+            format.output,
+            replace,
+        );
+
+        let mips = create_mips(device, size, settings);
+        let mip_bind_groups = mips
+            .iter()
+            .map(|mip| {
+                create_source_bind_group(
+                    device,
+                    &source_bind_group_layout,
+                    mip,
+                    &sampler,
+                    &settings_buffer,
+                )
+            })
+            .collect();
+
+        Self {
+            prefilter_pipeline,
+            downsample_pipeline,
+            upsample_pipeline,
+            composite_pipeline,
+            _vertex_shader: vertex_shader,
+            source_bind_group_layout,
+            scene_bind_group_layout,
+            sampler,
+            settings: *settings,
+            settings_buffer,
+            mips,
+            mip_bind_groups,
+            prefilter_bind_groups: BindGroupCache::new(),
+            scene_bind_groups: BindGroupCache::new(),
+            size: *size,
+        }
+    }
+
+    /// Uploads new settings into the uniform buffer.
+    ///
+    /// If the number of mip levels changes, the mip chain is reallocated.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the bloom.
+    pub(super) fn set_settings(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &BloomSettings,
+    ) {
+        let mip_levels = self.settings.mip_level_count(&self.size);
+        self.settings = *settings;
+        queue.write_buffer(&self.settings_buffer, 0, &settings.to_bytes());
+
+        if settings.mip_level_count(&self.size) != mip_levels {
+            self.recreate_mips(device);
+        }
+    }
+
+    /// Reallocates the mip chain and its bind groups for the current size and settings.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    fn recreate_mips(&mut self, device: &wgpu::Device) {
+        self.mips = create_mips(device, &self.size, &self.settings);
+        self.mip_bind_groups = self
+            .mips
+            .iter()
+            .map(|mip| {
+                create_source_bind_group(
+                    device,
+                    &self.source_bind_group_layout,
+                    mip,
+                    &self.sampler,
+                    &self.settings_buffer,
+                )
+            })
+            .collect();
+    }
+}
+
+impl EffectInner for BloomEffectInner {
+    /// Resizes the [BloomEffectInner] after creation.
+    ///
+    /// This reallocates the mip chain, whose number of levels depends on the size.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size to which the effect should be resized.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        self.size = *size;
+        self.recreate_mips(device);
+        self.prefilter_bind_groups.clear();
+        self.scene_bind_groups.clear();
+    }
+
+    /// Records all passes of the bloom which read from `input_view`
+    /// and write into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the passes are recorded.
+    /// * `input_view`: The view of the scene.
+    /// * `_inputs`: The additional inputs, which are not used by the bloom.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        _inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let prefilter_bind_group =
+            self.prefilter_bind_groups
+                .get_or_insert_with(input_view, || {
+                    create_source_bind_group(
+                        device,
+                        &self.source_bind_group_layout,
+                        input_view,
+                        &self.sampler,
+                        &self.settings_buffer,
+                    )
+                });
+        render(
+            encoder,
+            &self.prefilter_pipeline,
+            &[prefilter_bind_group],
+            &self.mips[0],
+            wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+        );
+
+        for level in 1..self.mips.len() {
+            render(
+                encoder,
+                &self.downsample_pipeline,
+                &[&self.mip_bind_groups[level - 1]],
+                &self.mips[level],
+                wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            );
+        }
+
+        // Every level is added onto the next larger one, which already contains its own downsample.
+        for level in (1..self.mips.len()).rev() {
+            render(
+                encoder,
+                &self.upsample_pipeline,
+                &[&self.mip_bind_groups[level]],
+                &self.mips[level - 1],
+                wgpu::LoadOp::Load,
+            );
+        }
+
+        let scene_bind_group = self.scene_bind_groups.get_or_insert_with(input_view, || {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(input_view),
+                }],
+                layout: &self.scene_bind_group_layout,
+            })
+        });
+        render(
+            encoder,
+            &self.composite_pipeline,
+            &[&self.mip_bind_groups[0], scene_bind_group],
+            output_view,
+            wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+        );
+    }
+}
+
+/// Creates the mip chain of the bloom, starting at half the size of the frame.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `size`: The size of the frame.
+/// * `settings`: The settings which limit the number of mip levels.
+///
+/// Returns:
+///
+/// One view for every mip level.
+fn create_mips(
+    device: &wgpu::Device,
+    size: &wgpu::Extent3d,
+    settings: &BloomSettings,
+) -> Vec<wgpu::TextureView> {
+    let mip_level_count = settings.mip_level_count(size);
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d {
+            width: (size.width / 2).max(1),
+            height: (size.height / 2).max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: MIP_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        label: None,
+        view_formats: &[],
+    });
+
+    (0..mip_level_count)
+        .map(|level| {
+            texture.create_view(&wgpu::TextureViewDescriptor {
+                base_mip_level: level,
+                mip_level_count: NonZeroU32::new(1),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Creates the pipeline of one of the bloom passes.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `bind_group_layouts`: The layouts of the bind groups of the pass.
+/// * `vertex_shader`: The shared fullscreen vertex shader.
+/// * `shader`: The shader module of the bloom.
+/// * `fragment_entry_point`: The name of the fragment shader function.
+/// * `format`: The format of the texture into which the pass renders.
+/// * `blend`: The blending of the pass with the content of the texture.
+///
+/// Returns:
+///
+/// The new pipeline.
+fn create_pipeline(
+    device: &wgpu::Device,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    vertex_shader: &VertexShader,
+    shader: &wgpu::ShaderModule,
+    fragment_entry_point: &str,
+    format: wgpu::TextureFormat,
+    blend: wgpu::BlendState,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts,
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: vertex_shader.state_with_uv(),
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: fragment_entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: Default::default(),
+        multisample: Default::default(),
+        depth_stencil: None,
+        multiview: None,
+    })
+}
+
+/// Creates the bind group which binds `source_view` as the source texture of a pass.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the source bind group, which is shared by all passes.
+/// * `source_view`: The view which is read by the pass.
+/// * `sampler`: The linear sampler used to sample the source.
+/// * `settings_buffer`: The uniform buffer containing the [BloomSettings].
+///
+/// Returns:
+///
+/// The new bind group.
+fn create_source_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    source_view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    settings_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(source_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: settings_buffer.as_entire_binding(),
+            },
+        ],
+        layout,
+    })
+}
+
+/// Records one of the bloom passes.
+///
+/// Arguments:
+///
+/// * `encoder`: The command encoder into which the pass is recorded.
+/// * `pipeline`: The pipeline of the pass.
+/// * `bind_groups`: The bind groups of the pass in the order of their indices.
+/// * `output_view`: The view into which the result is written.
+/// * `load`: Whether the `output_view` is cleared or its content is kept for blending.
+fn render(
+    encoder: &mut wgpu::CommandEncoder,
+    pipeline: &wgpu::RenderPipeline,
+    bind_groups: &[&wgpu::BindGroup],
+    output_view: &wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+) {
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: output_view,
+            resolve_target: None,
+            ops: wgpu::Operations { load, store: true },
+        })],
+        depth_stencil_attachment: None,
+        label: None,
+    });
+    rpass.set_pipeline(pipeline);
+    for (index, bind_group) in bind_groups.iter().enumerate() {
+        rpass.set_bind_group(index as u32, bind_group, &[]);
+    }
+    rpass.draw(0..3, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shader_matches_bind_group_layouts() {
+        let source = include_str!("shader/bloom.wgsl");
+        for entry_point in ["fs_prefilter", "fs_downsample", "fs_upsample"] {
+            validation::check_entry_point(
+                "bloom/shader/bloom.wgsl",
+                source,
+                naga::ShaderStage::Fragment,
+                entry_point,
+                &[&SOURCE_BIND_GROUP_LAYOUT_ENTRIES],
+            );
+        }
+        validation::check_entry_point(
+            "bloom/shader/bloom.wgsl",
+            source,
+            naga::ShaderStage::Fragment,
+            "fs_composite",
+            &[
+                &SOURCE_BIND_GROUP_LAYOUT_ENTRIES,
+                &SCENE_BIND_GROUP_LAYOUT_ENTRIES,
+            ],
+        );
+    }
+}
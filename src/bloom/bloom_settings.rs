@@ -0,0 +1,67 @@
+/// The settings of the [crate::bloom::BloomEffect].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BloomSettings {
+    /// The brightness above which pixels start to bloom.
+    ///
+    /// As the bloom is intended for HDR input, this is usually `1.0` or above.
+    pub threshold: f32,
+
+    /// The softness of the transition around the threshold in range `0..=1`,
+    /// relative to the threshold.
+    ///
+    /// A value of `0` results in a hard cut at the threshold.
+    pub soft_knee: f32,
+
+    /// The strength of the bloom when it is added onto the scene.
+    pub intensity: f32,
+
+    /// The radius of the upsampling filter in texels of the respective mip level.
+    ///
+    /// Larger values result in a wider, but blurrier bloom.
+    pub radius: f32,
+
+    /// The maximum number of mip levels of the bloom chain.
+    ///
+    /// Each level has half the size of the previous one, starting with half the
+    /// size of the frame. The number of levels is additionally limited, so that
+    /// the smallest level is at least one pixel wide and high.
+    /// Changing the number of levels reallocates the mip chain.
+    pub mip_levels: u32,
+}
+
+impl Default for BloomSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            soft_knee: 0.5,
+            intensity: 0.1,
+            radius: 1.0,
+            mip_levels: 6,
+        }
+    }
+}
+
+impl BloomSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 16;
+
+    /// Converts the settings into the layout of the `BloomSettings` struct in `bloom.wgsl`.
+    pub(super) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let knee = self.threshold * self.soft_knee.clamp(0.0, 1.0);
+
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.threshold.to_le_bytes());
+        bytes[4..8].copy_from_slice(&knee.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.intensity.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.radius.to_le_bytes());
+
+        bytes
+    }
+
+    /// Returns the number of mip levels which are used for a frame of the given `size`.
+    pub(super) fn mip_level_count(self, size: &wgpu::Extent3d) -> u32 {
+        let smallest_side = size.width.min(size.height).max(2);
+
+        self.mip_levels.clamp(1, smallest_side.ilog2())
+    }
+}
@@ -0,0 +1,5 @@
+mod bloom_effect;
+pub use bloom_effect::{BloomEffect, BloomFrame};
+mod bloom_effect_inner;
+mod bloom_settings;
+pub use bloom_settings::BloomSettings;
@@ -0,0 +1,120 @@
+// A physically based bloom, as presented by Jorge Jimenez in "Next Generation Post Processing in Call of Duty: Advanced Warfare".
+// See: http://www.iryoku.com/next-generation-post-processing-in-call-of-duty-advanced-warfare.
+
+#include "color.wgsl"
+#include "fullscreen.wgsl"
+#include "sampling.wgsl"
+
+struct BloomSettings {
+    threshold: f32,
+    knee: f32,
+    intensity: f32,
+    radius: f32,
+};
+
+@group(0) @binding(0) var source_texture : texture_2d<f32>;
+@group(0) @binding(1) var source_sampler : sampler;
+@group(0) @binding(2) var<uniform> settings : BloomSettings;
+
+// The scene onto which the bloom is composited, only used by `fs_composite`.
+@group(1) @binding(0) var scene_texture : texture_2d<f32>;
+
+fn source(uv: vec2<f32>) -> vec3<f32> {
+    return textureSampleLevel(source_texture, source_sampler, uv, 0.0).rgb;
+}
+
+// Removes everything below the threshold, using a quadratic curve around the threshold.
+fn apply_threshold(color: vec3<f32>) -> vec3<f32> {
+    let brightness = max(color.r, max(color.g, color.b));
+    var soft = clamp(brightness - settings.threshold + settings.knee, 0.0, 2.0 * settings.knee);
+    soft = soft * soft / (4.0 * settings.knee + 0.00001);
+    let contribution = max(soft, brightness - settings.threshold) / max(brightness, 0.00001);
+    return color * contribution;
+}
+
+// Accumulates a group of samples into the weighted sum of the downsampling filter.
+// The Karis average weights each group by its inverse luma, which suppresses fireflies.
+fn accumulate(sum: vec4<f32>, group: vec3<f32>, weight: f32, karis: bool) -> vec4<f32> {
+    var group_weight = weight;
+    if (karis) {
+        group_weight = group_weight / (1.0 + luma_rec709(group));
+    }
+    return sum + vec4<f32>(group * group_weight, group_weight);
+}
+
+// Downsamples the source using the 13 tap filter, which consists of 4 overlapping
+// 2x2 boxes at the corners and one 2x2 box in the center.
+fn downsample(uv: vec2<f32>, karis: bool) -> vec3<f32> {
+    let texel = texel_size(source_texture);
+
+    let a = source(uv + texel * vec2<f32>(-2.0, -2.0));
+    let b = source(uv + texel * vec2<f32>(0.0, -2.0));
+    let c = source(uv + texel * vec2<f32>(2.0, -2.0));
+    let d = source(uv + texel * vec2<f32>(-2.0, 0.0));
+    let e = source(uv);
+    let f = source(uv + texel * vec2<f32>(2.0, 0.0));
+    let g = source(uv + texel * vec2<f32>(-2.0, 2.0));
+    let h = source(uv + texel * vec2<f32>(0.0, 2.0));
+    let i = source(uv + texel * vec2<f32>(2.0, 2.0));
+    let j = source(uv + texel * vec2<f32>(-1.0, -1.0));
+    let k = source(uv + texel * vec2<f32>(1.0, -1.0));
+    let l = source(uv + texel * vec2<f32>(-1.0, 1.0));
+    let m = source(uv + texel * vec2<f32>(1.0, 1.0));
+
+    var sum = vec4<f32>(0.0);
+    sum = accumulate(sum, (a + b + d + e) * 0.25, 0.125, karis);
+    sum = accumulate(sum, (b + c + e + f) * 0.25, 0.125, karis);
+    sum = accumulate(sum, (d + e + g + h) * 0.25, 0.125, karis);
+    sum = accumulate(sum, (e + f + h + i) * 0.25, 0.125, karis);
+    sum = accumulate(sum, (j + k + l + m) * 0.25, 0.5, karis);
+
+    return sum.rgb / sum.a;
+}
+
+// Downsamples the scene into the first mip level, while applying the threshold.
+@fragment
+fn fs_prefilter(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    // The Karis average is only used for the first downsample, as described in the presentation.
+    return vec4<f32>(apply_threshold(downsample(in.uv, true)), 1.0);
+}
+
+// Downsamples one mip level into the next one.
+@fragment
+fn fs_downsample(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    return vec4<f32>(downsample(in.uv, false), 1.0);
+}
+
+// Upsamples the source using a 3x3 tent filter, whose size is scaled by the radius.
+fn upsample(uv: vec2<f32>) -> vec3<f32> {
+    let offset = settings.radius * texel_size(source_texture);
+
+    var color = source(uv) * 4.0;
+    color = color + (
+        source(uv + offset * vec2<f32>(0.0, -1.0)) +
+        source(uv + offset * vec2<f32>(-1.0, 0.0)) +
+        source(uv + offset * vec2<f32>(1.0, 0.0)) +
+        source(uv + offset * vec2<f32>(0.0, 1.0))
+    ) * 2.0;
+    color = color +
+        source(uv + offset * vec2<f32>(-1.0, -1.0)) +
+        source(uv + offset * vec2<f32>(1.0, -1.0)) +
+        source(uv + offset * vec2<f32>(-1.0, 1.0)) +
+        source(uv + offset * vec2<f32>(1.0, 1.0));
+
+    return color / 16.0;
+}
+
+// Upsamples one mip level, which is added onto the next larger one using additive blending.
+@fragment
+fn fs_upsample(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    return vec4<f32>(upsample(in.uv), 1.0);
+}
+
+// Upsamples the first mip level and adds it onto the scene.
+@fragment
+fn fs_composite(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    let scene = textureLoad(scene_texture, vec2<i32>(in.position.xy), 0);
+    let bloom = upsample(in.uv) * settings.intensity;
+
+    return vec4<f32>(scene.rgb + bloom, scene.a);
+}
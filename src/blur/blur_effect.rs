@@ -0,0 +1,43 @@
+use crate::{
+    effect_state::{impl_effect, EffectState},
+    EffectFrame,
+};
+
+use super::{blur_effect_inner::BlurEffectInner, BlurSettings};
+
+/// Blurs the frame using a separable Gaussian kernel or a dual Kawase blur.
+///
+/// Using [crate::blur::BlurMode::Gaussian] the frame is blurred horizontally into an
+/// intermediate texture and vertically into the output. The weights of the kernel are
+/// computed on the CPU from [BlurSettings::sigma] and neighboring texels are combined
+/// into a single bilinear sample, which halves the number of texture reads.
+///
+/// Using [crate::blur::BlurMode::DualKawase] the frame is downsampled through a chain of
+/// half resolution levels and upsampled again, which results in a large blur at a
+/// fraction of the cost, e.g. for blurred UI backdrops. The levels are reallocated
+/// together with the frame when the effect is resized.
+///
+/// Besides processing frames, [BlurEffect::apply] blurs arbitrary textures,
+/// so that the effect can be used as a building block by other passes.
+/// The input should have the size of the effect, but can use any filterable format.
+pub struct BlurEffect {
+    pub(super) state: EffectState<BlurEffectInner>,
+    pub(super) settings: BlurSettings,
+}
+
+/// The frame into which the scene is rendered before the [BlurEffect] is resolved.
+pub type BlurFrame<'a> = EffectFrame<'a, BlurEffect>;
+
+impl BlurEffect {
+    /// Creates the resources of the effect using the current size, format and settings.
+    fn create_inner(&self, device: &wgpu::Device) -> BlurEffectInner {
+        BlurEffectInner::new(
+            device,
+            &self.state.allocation_size(),
+            self.state.format(),
+            &self.settings,
+        )
+    }
+}
+
+impl_effect!(BlurEffect, "blur", BlurSettings);
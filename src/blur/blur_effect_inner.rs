@@ -0,0 +1,503 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    render_target::create_texture_view,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::{BlurMode, BlurSettings};
+
+/// The format of the intermediate textures, which keeps the range
+/// of HDR inputs and the precision between the passes.
+const INTERMEDIATE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// The entries of the bind group layout, which is shared by all passes
+/// and matches the bindings of `shader/blur.wgsl`.
+const BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 3] = [
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 2,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(BlurSettings::SIZE as u64),
+        },
+        count: None,
+    },
+];
+
+/// Wraps the internal fields and implementation
+/// of a [crate::blur::BlurEffect].
+pub(super) struct BlurEffectInner {
+    pub(super) horizontal_pipeline: wgpu::RenderPipeline,
+    pub(super) vertical_pipeline: wgpu::RenderPipeline,
+    pub(super) downsample_pipeline: wgpu::RenderPipeline,
+    pub(super) upsample_pipeline: wgpu::RenderPipeline,
+    pub(super) upsample_output_pipeline: wgpu::RenderPipeline,
+    pub(super) _vertex_shader: VertexShader,
+    pub(super) bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) sampler: wgpu::Sampler,
+    pub(super) settings: BlurSettings,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) levels: Vec<wgpu::TextureView>,
+    pub(super) level_bind_groups: Vec<wgpu::BindGroup>,
+    pub(super) input_bind_groups: BindGroupCache,
+    pub(super) size: wgpu::Extent3d,
+}
+
+impl BlurEffectInner {
+    /// Creates a new [BlurEffectInner].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The size of the frame which will later be processed.
+    /// * `format`: The texture format of the post-processed frame.
+    /// * `settings`: The settings of the blur.
+    ///
+    /// Returns:
+    ///
+    /// The new [BlurEffectInner].
+    pub(super) fn new(
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+        format: EffectFormat,
+        settings: &BlurSettings,
+    ) -> Self {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &settings.to_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &BIND_GROUP_LAYOUT_ENTRIES,
+        });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let blur_shader = include_composed_wgsl!(device, "shader/blur.wgsl");
+
+        let horizontal_pipeline = create_pipeline(
+            device,
+            &bind_group_layout,
+            &vertex_shader,
+            &blur_shader,
+            "fs_horizontal",
+            INTERMEDIATE_FORMAT,
+        );
+        let vertical_pipeline = create_pipeline(
+            device,
+            &bind_group_layout,
+            &vertex_shader,
+            &blur_shader,
+            "fs_vertical",
+            format.output,
+        );
+        let downsample_pipeline = create_pipeline(
+            device,
+            &bind_group_layout,
+            &vertex_shader,
+            &blur_shader,
+            "fs_downsample",
+            INTERMEDIATE_FORMAT,
+        );
+        let upsample_pipeline = create_pipeline(
+            device,
+            &bind_group_layout,
+            &vertex_shader,
+            &blur_shader,
+            "fs_upsample",
+            INTERMEDIATE_FORMAT,
+        );
+        let upsample_output_pipeline = create_pipeline(
+            device,
+            &bind_group_layout,
+            &vertex_shader,
+            &blur_shader,
+            "fs_upsample",
+            format.output,
+        );
+
+        let levels = create_levels(device, size, settings);
+        let level_bind_groups = levels
+            .iter()
+            .map(|level| {
+                create_bind_group(
+                    device,
+                    &bind_group_layout,
+                    level,
+                    &sampler,
+                    &settings_buffer,
+                )
+            })
+            .collect();
+
+        Self {
+            horizontal_pipeline,
+            vertical_pipeline,
+            downsample_pipeline,
+            upsample_pipeline,
+            upsample_output_pipeline,
+            _vertex_shader: vertex_shader,
+            bind_group_layout,
+            sampler,
+            settings: *settings,
+            settings_buffer,
+            levels,
+            level_bind_groups,
+            input_bind_groups: BindGroupCache::new(),
+            size: *size,
+        }
+    }
+
+    /// Uploads new settings into the uniform buffer.
+    ///
+    /// If the mode or the number of levels changes, the intermediate textures are reallocated.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the blur.
+    pub(super) fn set_settings(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &BlurSettings,
+    ) {
+        let mode = self.settings.mode;
+        let level_count = self.settings.level_count(&self.size);
+        self.settings = *settings;
+        queue.write_buffer(&self.settings_buffer, 0, &settings.to_bytes());
+
+        if settings.mode != mode || settings.level_count(&self.size) != level_count {
+            self.recreate_levels(device);
+        }
+    }
+
+    /// Reallocates the intermediate textures and their bind groups for the current size and settings.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    fn recreate_levels(&mut self, device: &wgpu::Device) {
+        self.levels = create_levels(device, &self.size, &self.settings);
+        self.level_bind_groups = self
+            .levels
+            .iter()
+            .map(|level| {
+                create_bind_group(
+                    device,
+                    &self.bind_group_layout,
+                    level,
+                    &self.sampler,
+                    &self.settings_buffer,
+                )
+            })
+            .collect();
+    }
+}
+
+impl EffectInner for BlurEffectInner {
+    /// Resizes the [BlurEffectInner] after creation.
+    ///
+    /// This reallocates the intermediate textures, whose number and size depend on the size.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size to which the effect should be resized.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        self.size = *size;
+        self.recreate_levels(device);
+        self.input_bind_groups.clear();
+    }
+
+    /// Records all passes of the blur which read from `input_view`
+    /// and write into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the passes are recorded.
+    /// * `input_view`: The view which will be blurred.
+    /// * `_inputs`: The additional inputs, which are not used by the blur.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        _inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let input_bind_group = self.input_bind_groups.get_or_insert_with(input_view, || {
+            create_bind_group(
+                device,
+                &self.bind_group_layout,
+                input_view,
+                &self.sampler,
+                &self.settings_buffer,
+            )
+        });
+
+        match self.settings.mode {
+            BlurMode::Gaussian => {
+                render(
+                    encoder,
+                    &self.horizontal_pipeline,
+                    input_bind_group,
+                    &self.levels[0],
+                );
+                render(
+                    encoder,
+                    &self.vertical_pipeline,
+                    &self.level_bind_groups[0],
+                    output_view,
+                );
+            }
+            BlurMode::DualKawase => {
+                render(
+                    encoder,
+                    &self.downsample_pipeline,
+                    input_bind_group,
+                    &self.levels[0],
+                );
+                for level in 1..self.levels.len() {
+                    render(
+                        encoder,
+                        &self.downsample_pipeline,
+                        &self.level_bind_groups[level - 1],
+                        &self.levels[level],
+                    );
+                }
+
+                // The content of every level is replaced by the upsampled next smaller level.
+                for level in (1..self.levels.len()).rev() {
+                    render(
+                        encoder,
+                        &self.upsample_pipeline,
+                        &self.level_bind_groups[level],
+                        &self.levels[level - 1],
+                    );
+                }
+                render(
+                    encoder,
+                    &self.upsample_output_pipeline,
+                    &self.level_bind_groups[0],
+                    output_view,
+                );
+            }
+        }
+    }
+}
+
+/// Creates the intermediate textures of the blur.
+///
+/// The Gaussian blur uses a single texture of the size of the frame for the result
+/// of the horizontal pass. The dual Kawase blur uses a chain of textures starting at
+/// half the size of the frame, where each texture has half the size of the previous one.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `size`: The size of the frame.
+/// * `settings`: The settings which select the mode and limit the number of levels.
+///
+/// Returns:
+///
+/// One view for every level.
+fn create_levels(
+    device: &wgpu::Device,
+    size: &wgpu::Extent3d,
+    settings: &BlurSettings,
+) -> Vec<wgpu::TextureView> {
+    let first_level = match settings.mode {
+        BlurMode::Gaussian => 0,
+        BlurMode::DualKawase => 1,
+    };
+
+    (first_level..first_level + settings.level_count(size))
+        .map(|level| {
+            let size = wgpu::Extent3d {
+                width: (size.width >> level).max(1),
+                height: (size.height >> level).max(1),
+                depth_or_array_layers: 1,
+            };
+            create_texture_view(device, &size, INTERMEDIATE_FORMAT)
+        })
+        .collect()
+}
+
+/// Creates the pipeline of one of the blur passes.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `bind_group_layout`: The layout of the bind group, which is shared by all passes.
+/// * `vertex_shader`: The shared fullscreen vertex shader.
+/// * `shader`: The shader module of the blur.
+/// * `fragment_entry_point`: The name of the fragment shader function.
+/// * `format`: The format of the texture into which the pass renders.
+///
+/// Returns:
+///
+/// The new pipeline.
+fn create_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    vertex_shader: &VertexShader,
+    shader: &wgpu::ShaderModule,
+    fragment_entry_point: &str,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: vertex_shader.state_with_uv(),
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: fragment_entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent::REPLACE,
+                    alpha: wgpu::BlendComponent::REPLACE,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: Default::default(),
+        multisample: Default::default(),
+        depth_stencil: None,
+        multiview: None,
+    })
+}
+
+/// Creates the bind group which binds `source_view` as the source texture of a pass.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the bind group, which is shared by all passes.
+/// * `source_view`: The view which is read by the pass.
+/// * `sampler`: The linear sampler used to sample the source.
+/// * `settings_buffer`: The uniform buffer containing the [BlurSettings].
+///
+/// Returns:
+///
+/// The new bind group.
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    source_view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    settings_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(source_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: settings_buffer.as_entire_binding(),
+            },
+        ],
+        layout,
+    })
+}
+
+/// Records one of the blur passes.
+///
+/// Arguments:
+///
+/// * `encoder`: The command encoder into which the pass is recorded.
+/// * `pipeline`: The pipeline of the pass.
+/// * `bind_group`: The bind group which contains the source texture.
+/// * `output_view`: The view into which the result is written.
+fn render(
+    encoder: &mut wgpu::CommandEncoder,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+    output_view: &wgpu::TextureView,
+) {
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: output_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: true,
+            },
+        })],
+        depth_stencil_attachment: None,
+        label: None,
+    });
+    rpass.set_pipeline(pipeline);
+    rpass.set_bind_group(0, bind_group, &[]);
+    rpass.draw(0..3, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shader_matches_bind_group_layout() {
+        for entry_point in [
+            "fs_horizontal",
+            "fs_vertical",
+            "fs_downsample",
+            "fs_upsample",
+        ] {
+            validation::check_entry_point(
+                "blur/shader/blur.wgsl",
+                include_str!("shader/blur.wgsl"),
+                naga::ShaderStage::Fragment,
+                entry_point,
+                &[&BIND_GROUP_LAYOUT_ENTRIES],
+            );
+        }
+    }
+}
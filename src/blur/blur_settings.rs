@@ -0,0 +1,230 @@
+/// The largest supported [BlurSettings::radius] in pixels.
+const MAX_RADIUS: u32 = 32;
+
+/// The largest number of bilinear taps on one side of the kernel, including the center.
+const MAX_TAPS: usize = 1 + (MAX_RADIUS as usize).div_ceil(2);
+
+/// The algorithms of the [crate::blur::BlurEffect].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlurMode {
+    /// A separable Gaussian blur at full resolution, configured by
+    /// [BlurSettings::sigma] and [BlurSettings::radius].
+    #[default]
+    Gaussian,
+    /// A dual Kawase blur, which downsamples the frame through a chain of half
+    /// resolution levels and upsamples it again, configured by
+    /// [BlurSettings::iterations] and [BlurSettings::offset].
+    ///
+    /// This is much cheaper for large radii, e.g. for blurred UI backdrops,
+    /// but only approximates a Gaussian blur.
+    DualKawase,
+}
+
+/// The settings of the [crate::blur::BlurEffect].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlurSettings {
+    /// The algorithm of the blur.
+    ///
+    /// Changing the algorithm reallocates the intermediate textures.
+    pub mode: BlurMode,
+
+    /// The standard deviation of the Gaussian kernel in pixels.
+    ///
+    /// Larger values result in a stronger blur. A value of `0` disables the blur.
+    pub sigma: f32,
+
+    /// The number of pixels on each side of the center which are covered by the kernel.
+    ///
+    /// The radius should be around `3 * sigma`, smaller values cut off the kernel
+    /// and larger values only add texture reads with negligible weights.
+    /// It is clamped to `0..=32`.
+    pub radius: u32,
+
+    /// The number of half resolution levels of the dual Kawase blur.
+    ///
+    /// Every level roughly doubles the radius of the blur. The number of levels is
+    /// additionally limited, so that the smallest level is at least one pixel wide
+    /// and high, and at least one level is always used.
+    /// Changing the number of levels reallocates them.
+    pub iterations: u32,
+
+    /// The distance of the samples of the dual Kawase blur in half texels of the respective level.
+    ///
+    /// Larger values widen the blur without additional levels, but values much larger
+    /// than `1` result in visible artifacts.
+    pub offset: f32,
+}
+
+impl Default for BlurSettings {
+    fn default() -> Self {
+        Self {
+            mode: BlurMode::default(),
+            sigma: 4.0,
+            radius: 12,
+            iterations: 4,
+            offset: 1.0,
+        }
+    }
+}
+
+impl BlurSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 16 + MAX_TAPS * 16;
+
+    /// Computes the normalized weights of the discrete Gaussian kernel.
+    ///
+    /// Returns:
+    ///
+    /// The weights of the center pixel and the pixels on one side of it,
+    /// which are mirrored on the other side.
+    pub(super) fn weights(self) -> Vec<f32> {
+        let radius = self.radius.min(MAX_RADIUS);
+        if self.sigma <= 0.0 {
+            return vec![1.0];
+        }
+
+        let weights: Vec<f32> = (0..=radius)
+            .map(|i| (-((i * i) as f32) / (2.0 * self.sigma * self.sigma)).exp())
+            .collect();
+        let sum = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+
+        weights.into_iter().map(|weight| weight / sum).collect()
+    }
+
+    /// Combines pairs of neighboring pixels of the kernel into single bilinear taps.
+    ///
+    /// Sampling between two pixels with a linear sampler returns their average weighted
+    /// by the distance to the sample position, so that choosing the position by the
+    /// ratio of their weights samples both pixels at once.
+    /// See: https://www.rastergrid.com/blog/2010/09/efficient-gaussian-blur-with-linear-sampling/.
+    ///
+    /// Returns:
+    ///
+    /// The offsets in pixels and the weights of the taps, starting with the center.
+    pub(super) fn taps(self) -> Vec<(f32, f32)> {
+        let weights = self.weights();
+
+        let mut taps = vec![(0.0, weights[0])];
+        for (i, pair) in weights[1..].chunks(2).enumerate() {
+            let offset = (2 * i + 1) as f32;
+            let tap = match pair {
+                [a, b] => (offset + b / (a + b), a + b),
+                _ => (offset, pair[0]),
+            };
+            taps.push(tap);
+        }
+
+        taps
+    }
+
+    /// Converts the settings into the layout of the `BlurSettings` struct in `blur.wgsl`.
+    pub(super) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let taps = self.taps();
+
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&(taps.len() as u32).to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.offset.to_le_bytes());
+        for (i, (offset, weight)) in taps.iter().enumerate() {
+            let start = 16 + i * 16;
+            bytes[start..start + 4].copy_from_slice(&offset.to_le_bytes());
+            bytes[start + 4..start + 8].copy_from_slice(&weight.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Returns the number of intermediate textures which are used for a frame of the given `size`.
+    ///
+    /// The Gaussian blur uses a single texture of the size of the frame, while the
+    /// dual Kawase blur uses one texture for every iteration, starting at half the size.
+    pub(super) fn level_count(self, size: &wgpu::Extent3d) -> u32 {
+        match self.mode {
+            BlurMode::Gaussian => 1,
+            BlurMode::DualKawase => {
+                let smallest_side = size.width.min(size.height).max(2);
+                self.iterations.clamp(1, smallest_side.ilog2())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_are_normalized() {
+        for radius in [0, 1, 2, 7, 12, MAX_RADIUS, MAX_RADIUS + 8] {
+            let settings = BlurSettings {
+                sigma: 4.0,
+                radius,
+                ..Default::default()
+            };
+            let weights = settings.weights();
+            let sum = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+            assert!((sum - 1.0).abs() < 1e-5, "radius {radius}: {sum}");
+            assert!(weights.windows(2).all(|pair| pair[0] > pair[1]));
+        }
+    }
+
+    #[test]
+    fn taps_sample_the_same_kernel() {
+        for radius in [0, 1, 2, 5, 12, MAX_RADIUS] {
+            let settings = BlurSettings {
+                sigma: 3.0,
+                radius,
+                ..Default::default()
+            };
+            let weights = settings.weights();
+            let taps = settings.taps();
+            assert!(taps.len() <= MAX_TAPS);
+
+            // Reconstructs the weight of every pixel from the linear interpolation of the taps.
+            let mut reconstructed = vec![0.0; weights.len()];
+            for (offset, weight) in taps {
+                let pixel = offset.floor() as usize;
+                let fraction = offset.fract();
+                reconstructed[pixel] += weight * (1.0 - fraction);
+                if fraction > 0.0 {
+                    reconstructed[pixel + 1] += weight * fraction;
+                }
+            }
+            for (expected, actual) in weights.iter().zip(reconstructed) {
+                assert!((expected - actual).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_sigma_disables_the_blur() {
+        let settings = BlurSettings {
+            sigma: 0.0,
+            radius: 12,
+            ..Default::default()
+        };
+        assert_eq!(settings.taps(), vec![(0.0, 1.0)]);
+    }
+
+    #[test]
+    fn kawase_levels_fit_into_the_frame() {
+        let settings = BlurSettings {
+            mode: BlurMode::DualKawase,
+            iterations: 6,
+            ..Default::default()
+        };
+        let size = |width, height| wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        assert_eq!(settings.level_count(&size(1920, 1080)), 6);
+        assert_eq!(settings.level_count(&size(1920, 20)), 4);
+        assert_eq!(settings.level_count(&size(1, 1)), 1);
+
+        let settings = BlurSettings {
+            iterations: 0,
+            ..settings
+        };
+        assert_eq!(settings.level_count(&size(1920, 1080)), 1);
+    }
+}
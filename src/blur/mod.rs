@@ -0,0 +1,5 @@
+mod blur_effect;
+pub use blur_effect::{BlurEffect, BlurFrame};
+mod blur_effect_inner;
+mod blur_settings;
+pub use blur_settings::{BlurMode, BlurSettings};
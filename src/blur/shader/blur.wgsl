@@ -0,0 +1,84 @@
+// A separable Gaussian blur, which combines two texels of the kernel into a single bilinear tap.
+// See: https://www.rastergrid.com/blog/2010/09/efficient-gaussian-blur-with-linear-sampling/.
+//
+// And the dual Kawase blur, as presented by Marius Bjorge in "Bandwidth-Efficient Rendering"
+// at SIGGRAPH 2015, which blurs while downsampling and upsampling through a chain of levels.
+
+#include "fullscreen.wgsl"
+#include "sampling.wgsl"
+
+struct BlurSettings {
+    tap_count: u32,
+    // The distance of the dual Kawase samples in half texels.
+    offset: f32,
+    // The offset in texels (x) and the weight (y) of every tap, starting with the center.
+    // The taps are mirrored on the other side of the center.
+    taps: array<vec4<f32>, 17>,
+};
+
+@group(0) @binding(0) var source_texture : texture_2d<f32>;
+@group(0) @binding(1) var source_sampler : sampler;
+@group(0) @binding(2) var<uniform> settings : BlurSettings;
+
+fn source(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(source_texture, source_sampler, uv, 0.0);
+}
+
+// Blurs the source along `direction`, which is given in texels.
+fn blur(uv: vec2<f32>, direction: vec2<f32>) -> vec4<f32> {
+    let texel = direction * texel_size(source_texture);
+
+    var color = source(uv) * settings.taps[0].y;
+    for (var i = 1u; i < settings.tap_count; i = i + 1u) {
+        let tap = settings.taps[i];
+        let offset = texel * tap.x;
+        color = color + (source(uv - offset) + source(uv + offset)) * tap.y;
+    }
+
+    return color;
+}
+
+// Blurs the source horizontally into the intermediate texture.
+@fragment
+fn fs_horizontal(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    return blur(in.uv, vec2<f32>(1.0, 0.0));
+}
+
+// Blurs the intermediate texture vertically into the output.
+@fragment
+fn fs_vertical(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    return blur(in.uv, vec2<f32>(0.0, 1.0));
+}
+
+// Downsamples the source into the next smaller level using the center and four diagonal samples.
+@fragment
+fn fs_downsample(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    let offset = 0.5 * settings.offset * texel_size(source_texture);
+
+    var color = source(in.uv) * 4.0;
+    color = color + source(in.uv + offset * vec2<f32>(-1.0, -1.0));
+    color = color + source(in.uv + offset * vec2<f32>(1.0, -1.0));
+    color = color + source(in.uv + offset * vec2<f32>(-1.0, 1.0));
+    color = color + source(in.uv + offset * vec2<f32>(1.0, 1.0));
+
+    return color / 8.0;
+}
+
+// Upsamples the source into the next larger level or the output using a ring of eight samples.
+@fragment
+fn fs_upsample(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    let offset = 0.5 * settings.offset * texel_size(source_texture);
+
+    var color = source(in.uv + offset * vec2<f32>(-2.0, 0.0));
+    color = color + source(in.uv + offset * vec2<f32>(2.0, 0.0));
+    color = color + source(in.uv + offset * vec2<f32>(0.0, -2.0));
+    color = color + source(in.uv + offset * vec2<f32>(0.0, 2.0));
+    color = color + (
+        source(in.uv + offset * vec2<f32>(-1.0, -1.0)) +
+        source(in.uv + offset * vec2<f32>(1.0, -1.0)) +
+        source(in.uv + offset * vec2<f32>(-1.0, 1.0)) +
+        source(in.uv + offset * vec2<f32>(1.0, 1.0))
+    ) * 2.0;
+
+    return color / 12.0;
+}
@@ -0,0 +1,488 @@
+use crate::{
+    blit::{Blit, LazyBlit},
+    effect_frame::{FrameEffect, FrameInputs},
+    render_target::{self, RenderTarget},
+    DynEffect, Effect, EffectFormat, EffectFrame,
+};
+
+/// Applies an ordered list of effects one after another.
+///
+/// The intermediate results are stored in at most two textures which are
+/// used alternately as input and output of the individual stages.
+///
+/// Only the last effect of the chain writes into the output view, all other effects
+/// write into the intermediate textures. If the last effect is disabled, the result
+/// of the previous effects is copied into the output view.
+///
+/// The chained effects are only applied using [DynEffect::apply_with_inputs], so that they
+/// never allocate the texture of their own frames. The inputs of a frame, e.g. its depth
+/// texture, are passed to every effect of the chain.
+pub struct EffectChain {
+    pub(super) effects: Vec<Box<dyn DynEffect>>,
+    pub(super) targets: Vec<RenderTarget>,
+    passthrough: Option<Blit>,
+    image_blit: LazyBlit,
+    format: EffectFormat,
+    size: wgpu::Extent3d,
+    enabled: bool,
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    depth_format: Option<wgpu::TextureFormat>,
+}
+
+/// The frame into which the scene is rendered before the effects of an [EffectChain] are resolved.
+pub type EffectChainFrame<'a> = EffectFrame<'a, EffectChain>;
+
+impl EffectChain {
+    /// Creates a new [EffectChain].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The size of the frame which will later be processed.
+    /// * `format`: The texture format of the post-processed frame, optionally
+    ///   using a separate intermediate format (see [EffectFormat]).
+    /// * `effects`: The effects in the order in which they should be applied.
+    ///   The last effect must have been created using the output format of the chain
+    ///   and all other effects using its intermediate format as output format.
+    ///
+    /// Returns:
+    ///
+    /// The new [EffectChain].
+    pub fn new(
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+        format: impl Into<EffectFormat>,
+        effects: Vec<Box<dyn DynEffect>>,
+    ) -> EffectChain {
+        let format = format.into();
+        let format =
+            EffectFormat::new(format.intermediate_or(device, format.output), format.output);
+        let mut chain = EffectChain {
+            effects,
+            targets: Vec::new(),
+            passthrough: None,
+            image_blit: LazyBlit::default(),
+            format,
+            size: *size,
+            enabled: true,
+            #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+            depth_format: None,
+        };
+        chain.create_targets(device);
+
+        chain
+    }
+
+    /// Allocates or releases a depth texture owned by the frames of this chain.
+    ///
+    /// The depth texture can be used as the depth attachment while rendering into
+    /// a frame using [crate::EffectFrame::depth_view] and is read by all effects of
+    /// the chain which need the depth of the scene, unless the frame borrows another
+    /// depth texture. The depth textures of the chained effects are never used.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `depth_format`: The format of the depth texture, `None` releases the depth texture.
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    pub fn set_depth_format(
+        &mut self,
+        device: &wgpu::Device,
+        depth_format: Option<wgpu::TextureFormat>,
+    ) {
+        self.depth_format = depth_format;
+        let size = render_target::allocation_size(&self.size);
+        if let Some(target) = self.targets.first_mut() {
+            target.set_depth_format(device, &size, depth_format);
+        }
+    }
+
+    /// Returns the effects of the chain in the order in which they are applied.
+    ///
+    /// This allows e.g. enabling or disabling individual effects using [DynEffect::set_enabled].
+    pub fn effects_mut(&mut self) -> &mut [Box<dyn DynEffect>] {
+        &mut self.effects
+    }
+
+    /// Returns whether the effect chain is applied.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables the effect chain.
+    ///
+    /// Disabling the chain releases its intermediate textures immediately and frames
+    /// are rendered directly into the output view. The effects of the chain keep
+    /// their resources, unless they are disabled individually using [EffectChain::effects_mut].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `enabled`: Whether the chain should be applied.
+    pub fn set_enabled(&mut self, device: &wgpu::Device, enabled: bool) {
+        if !enabled {
+            self.targets.clear();
+        } else if !self.enabled {
+            self.create_targets(device);
+        }
+        self.enabled = enabled;
+    }
+
+    /// Returns whether the effect chain is suspended, because its size is empty.
+    ///
+    /// The effects of a suspended chain are suspended as well and
+    /// its frames are rendered directly into the output view.
+    pub fn is_suspended(&self) -> bool {
+        render_target::is_empty(&self.size)
+    }
+
+    /// Returns whether any effect of the chain is applied.
+    fn is_active(&self) -> bool {
+        self.enabled
+            && !self.is_suspended()
+            && self.effects.iter().any(|effect| effect.is_enabled())
+    }
+
+    /// Creates the intermediate textures needed to apply the effects of the chain,
+    /// together with the depth texture of the frames.
+    ///
+    /// A single effect only needs one texture to render into, while
+    /// every longer chain can alternate between two textures.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    fn create_targets(&mut self, device: &wgpu::Device) {
+        let size = render_target::allocation_size(&self.size);
+        let intermediate = self.format.intermediate.unwrap_or(self.format.output);
+        self.targets = (0..self.effects.len().min(2))
+            .map(|_| RenderTarget::new(device, &size, intermediate, 1))
+            .collect();
+        #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+        self.set_depth_format(device, self.depth_format);
+    }
+
+    /// Applies all effects of the chain to an arbitrary `input_view`.
+    ///
+    /// In contrast to [Effect::start_frame] the input does not need to be
+    /// rendered into the texture owned by the chain, which avoids a copy
+    /// if the input already exists as a texture.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effects are recorded.
+    /// * `input_view`: The view which is the input of the first effect.
+    /// * `output_view`: The view into which the last effect writes its result.
+    pub fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        output_view: &wgpu::TextureView,
+    ) {
+        self.encode(
+            device,
+            encoder,
+            Some(input_view),
+            &FrameInputs::default(),
+            output_view,
+        );
+    }
+
+    /// Records all effects of the chain into the given `encoder`.
+    ///
+    /// If the chain is disabled or suspended, `input_view` is copied into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effects are recorded.
+    /// * `input_view`: The view which is the input of the first effect or `None`
+    ///   if the chain's own first intermediate texture should be used.
+    /// * `inputs`: The additional inputs which are passed to every effect. Without a depth
+    ///   texture, the depth texture owned by the frames is used if `input_view` is `None`.
+    /// * `output_view`: The view into which the last effect writes its result.
+    fn encode(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: Option<&wgpu::TextureView>,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+        let inputs = &FrameInputs {
+            depth_view: match (inputs.depth_texture, input_view) {
+                (None, None) => self
+                    .targets
+                    .first()
+                    .and_then(|target| target.sampled_depth_view()),
+                _ => inputs.depth_view,
+            },
+            ..*inputs
+        };
+
+        // The view which still has to be copied into the output view.
+        let mut remaining = input_view;
+
+        if self.enabled && !self.is_suspended() {
+            // Disabled effects are skipped, so that the intermediate
+            // textures only alternate between the enabled effects.
+            let last = self.effects.len().saturating_sub(1);
+            let mut stage = 0;
+            for (i, effect) in self.effects.iter_mut().enumerate() {
+                if !effect.is_enabled() {
+                    continue;
+                }
+
+                let stage_input = match input_view {
+                    Some(input_view) if stage == 0 => input_view,
+                    _ => self.targets[stage % 2].view(),
+                };
+                let stage_output = if i == last {
+                    output_view
+                } else {
+                    self.targets[(stage + 1) % 2].view()
+                };
+                effect.apply_with_inputs(device, encoder, stage_input, inputs, stage_output);
+                stage += 1;
+
+                remaining = (i != last).then(|| self.targets[stage % 2].view());
+            }
+        }
+
+        // Only the last effect was created for the output format, so that the result is copied
+        // if it is disabled. Without any enabled effect, frames are rendered into the output view.
+        if let Some(remaining) = remaining {
+            let format = self.format.output;
+            self.passthrough
+                .get_or_insert_with(|| Blit::new(device, format))
+                .apply(device, encoder, remaining, output_view);
+        }
+    }
+}
+
+impl Effect for EffectChain {
+    type Frame<'a> = EffectChainFrame<'a>;
+
+    /// Resizes the effect chain and all of its effects after creation.
+    ///
+    /// This should be called when the main surface is resized, so that
+    /// no new [Effect] must be created which is slightly faster.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size to which the effect should be resized.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        self.size = *size;
+        if self.enabled {
+            self.create_targets(device);
+        }
+
+        for effect in self.effects.iter_mut() {
+            effect.resize(device, size);
+        }
+    }
+
+    /// Creates a new [EffectChainFrame].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `queue`: The command queue where the post-processing should be applied.
+    /// * `output_view`: The view into which the frame will be resolved.
+    ///
+    /// Returns:
+    ///
+    /// The new [EffectChainFrame].
+    fn start_frame<'a>(
+        &'a mut self,
+        device: &'a wgpu::Device,
+        queue: &'a wgpu::Queue,
+        output_view: &'a wgpu::TextureView,
+    ) -> EffectChainFrame<'a> {
+        EffectFrame::new(self, device, queue, output_view)
+    }
+}
+
+impl FrameEffect for EffectChain {
+    fn frame_target(&self) -> Option<&RenderTarget> {
+        self.targets.first().filter(|_| self.is_active())
+    }
+
+    fn image_blit(&mut self) -> &mut LazyBlit {
+        &mut self.image_blit
+    }
+
+    fn resolve_frame(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        self.encode(device, encoder, None, inputs, output_view);
+    }
+}
+
+#[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+impl crate::effect_frame::DepthEffect for EffectChain {}
+
+#[cfg(feature = "taa")]
+impl<'a> EffectChainFrame<'a> {
+    /// Sets the motion vectors of the scene, which are passed to every
+    /// [crate::taa::TaaEffect] of the chain.
+    ///
+    /// See [crate::taa::TaaFrame::set_velocity_view].
+    ///
+    /// Arguments:
+    ///
+    /// * `velocity_view`: The view of the velocity texture which was written while rendering into this frame.
+    pub fn set_velocity_view(&mut self, velocity_view: &'a wgpu::TextureView) {
+        self.inputs.velocity_view = Some(velocity_view);
+    }
+}
+
+impl DynEffect for EffectChain {
+    /// Resizes the effect chain and all of its effects after creation.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size to which the effect should be resized.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        Effect::resize(self, device, size);
+    }
+
+    /// Returns whether the effect chain is applied.
+    fn is_enabled(&self) -> bool {
+        EffectChain::is_enabled(self)
+    }
+
+    /// Enables or disables the effect chain.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `queue`: The command queue which is used to upload resources.
+    /// * `enabled`: Whether the chain should be applied.
+    fn set_enabled(&mut self, device: &wgpu::Device, _queue: &wgpu::Queue, enabled: bool) {
+        EffectChain::set_enabled(self, device, enabled);
+    }
+
+    /// Records all effects of the chain into the given `encoder`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effects are recorded.
+    /// * `input_view`: The view which is the input of the first effect.
+    /// * `output_view`: The view into which the last effect writes its result.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        output_view: &wgpu::TextureView,
+    ) {
+        EffectChain::apply(self, device, encoder, input_view, output_view);
+    }
+
+    /// Records all effects of the chain into the given `encoder`, passing `inputs` to every effect.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effects are recorded.
+    /// * `input_view`: The view which is the input of the first effect.
+    /// * `inputs`: The additional inputs which have been set on the frame.
+    /// * `output_view`: The view into which the last effect writes its result.
+    fn apply_with_inputs(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        self.encode(device, encoder, Some(input_view), inputs, output_view);
+    }
+}
+
+#[cfg(all(test, any(feature = "dof", feature = "smaa", feature = "taa")))]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::Frame;
+
+    /// An effect which records whether the inputs of a frame reached it.
+    struct RecordingEffect {
+        depth: Rc<Cell<bool>>,
+    }
+
+    impl DynEffect for RecordingEffect {
+        fn resize(&mut self, _device: &wgpu::Device, _size: &wgpu::Extent3d) {}
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn set_enabled(&mut self, _device: &wgpu::Device, _queue: &wgpu::Queue, _enabled: bool) {}
+
+        fn apply(
+            &mut self,
+            _device: &wgpu::Device,
+            _encoder: &mut wgpu::CommandEncoder,
+            _input_view: &wgpu::TextureView,
+            _output_view: &wgpu::TextureView,
+        ) {
+            self.depth.set(false);
+        }
+
+        fn apply_with_inputs(
+            &mut self,
+            _device: &wgpu::Device,
+            _encoder: &mut wgpu::CommandEncoder,
+            _input_view: &wgpu::TextureView,
+            inputs: &FrameInputs<'_>,
+            _output_view: &wgpu::TextureView,
+        ) {
+            self.depth.set(inputs.depth_view.is_some());
+        }
+    }
+
+    #[test]
+    fn frames_pass_their_depth_to_every_effect() {
+        let Some((device, queue)) = crate::test_device::device() else {
+            return;
+        };
+
+        let size = wgpu::Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        };
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let output_view = render_target::create_texture_view(&device, &size, format);
+
+        let depths: Vec<_> = (0..3).map(|_| Rc::new(Cell::new(false))).collect();
+        let effects = depths
+            .iter()
+            .map(|depth| {
+                let depth = depth.clone();
+                Box::new(RecordingEffect { depth }) as Box<dyn DynEffect>
+            })
+            .collect();
+        let mut chain = EffectChain::new(&device, &size, format, effects);
+        chain.set_depth_format(&device, Some(wgpu::TextureFormat::Depth32Float));
+
+        let frame = chain.start_frame(&device, &queue, &output_view);
+        assert!(frame.depth_view().is_some());
+        frame.resolve();
+
+        assert!(depths.iter().all(|depth| depth.get()));
+    }
+}
@@ -0,0 +1,2 @@
+mod effect_chain;
+pub use effect_chain::{EffectChain, EffectChainFrame};
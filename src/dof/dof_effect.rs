@@ -0,0 +1,76 @@
+use crate::{
+    effect_frame::{validate_depth_texture, FrameInputs},
+    effect_state::{impl_effect, EffectState},
+    EffectFrame, Error,
+};
+
+use super::{dof_effect_inner::DofEffectInner, DofSettings};
+
+/// Simulates the depth of field (DoF) of a camera lens by blurring the parts
+/// of a frame which are in front of or behind the focal plane.
+///
+/// The circle of confusion (CoC) of every pixel is computed from the depth of the scene,
+/// see [DofSettings]. The frame is split into a near and a far field at half resolution,
+/// which are blurred separately by gathering a disc shaped bokeh and composited onto
+/// the sharp frame. Blurring the near field separately lets out of focus objects in
+/// front bleed over the background, without the background bleeding into them.
+///
+/// The effect panics if it is applied without a depth texture, e.g. by [DofEffect::apply]
+/// or within an [crate::EffectChain] without a depth texture. Use [DofEffect::apply_with_depth],
+/// [DofFrame::set_depth_texture] or [DofEffect::set_depth_format] instead.
+pub struct DofEffect {
+    pub(super) state: EffectState<DofEffectInner>,
+    pub(super) settings: DofSettings,
+}
+
+/// The frame into which the scene is rendered before the [DofEffect] is resolved.
+pub type DofFrame<'a> = EffectFrame<'a, DofEffect>;
+
+impl DofEffect {
+    /// Applies the depth of field effect to an arbitrary `input_view`
+    /// using the depth of the scene to compute the circle of confusion.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `input_view`: The view which will be blurred.
+    /// * `depth_texture`: The depth texture which belongs to `input_view`.
+    /// * `output_view`: The view into which the result is written.
+    ///
+    /// Returns:
+    ///
+    /// An [Error] if the depth texture is multisampled or cannot be bound,
+    /// in which case nothing is recorded.
+    pub fn apply_with_depth(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        depth_texture: &wgpu::Texture,
+        output_view: &wgpu::TextureView,
+    ) -> Result<(), Error> {
+        validate_depth_texture(depth_texture)?;
+
+        let inputs = FrameInputs {
+            depth_texture: Some(depth_texture),
+            ..Default::default()
+        };
+        self.state
+            .apply(device, encoder, input_view, &inputs, output_view);
+
+        Ok(())
+    }
+
+    /// Creates the resources of the effect using the current size, format and settings.
+    fn create_inner(&self, device: &wgpu::Device) -> DofEffectInner {
+        DofEffectInner::new(
+            device,
+            &self.state.allocation_size(),
+            self.state.format(),
+            &self.settings,
+        )
+    }
+}
+
+impl_effect!(DofEffect, "depth of field", DofSettings, depth);
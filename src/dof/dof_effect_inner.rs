@@ -0,0 +1,491 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    render_target::create_texture_view,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::DofSettings;
+
+/// The format of the near and far field, which keeps the precision of HDR inputs
+/// and stores the CoC in the alpha channel.
+const FIELD_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// The entries of the layout of the settings bind group, which matches group 0 of `shader/dof.wgsl`.
+const SETTINGS_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 2] = [
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(DofSettings::SIZE as u64),
+        },
+        count: None,
+    },
+];
+
+/// The entries of the layout of the input bind group, which matches group 1 of `shader/dof.wgsl`.
+const INPUT_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 1] = [texture_layout_entry(
+    0,
+    wgpu::TextureSampleType::Float { filterable: true },
+)];
+
+/// The entries of the layout of the depth bind group, which matches group 2 of `shader/dof.wgsl`.
+const DEPTH_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 1] = [texture_layout_entry(
+    0,
+    wgpu::TextureSampleType::Float { filterable: false },
+)];
+
+/// The entries of the layout of the field bind group, which matches group 3 of `shader/dof.wgsl`.
+const FIELD_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 2] = [
+    texture_layout_entry(0, wgpu::TextureSampleType::Float { filterable: true }),
+    texture_layout_entry(1, wgpu::TextureSampleType::Float { filterable: true }),
+];
+
+/// The far and near field at half resolution together with the bind group
+/// which binds them as the input of the next pass.
+pub(super) struct Fields {
+    pub(super) far: wgpu::TextureView,
+    pub(super) near: wgpu::TextureView,
+    pub(super) bind_group: wgpu::BindGroup,
+}
+
+/// Wraps the internal fields and implementation
+/// of a [crate::dof::DofEffect].
+pub(super) struct DofEffectInner {
+    pub(super) prefilter_pipeline: wgpu::RenderPipeline,
+    pub(super) gather_pipeline: wgpu::RenderPipeline,
+    pub(super) composite_pipeline: wgpu::RenderPipeline,
+    pub(super) _vertex_shader: VertexShader,
+    pub(super) input_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) depth_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) field_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) settings_bind_group: wgpu::BindGroup,
+    pub(super) settings: DofSettings,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) bind_groups: BindGroupCache,
+    pub(super) prefiltered: Fields,
+    pub(super) gathered: Fields,
+}
+
+impl DofEffectInner {
+    /// Creates a new [DofEffectInner].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The size of the frame which will later be processed.
+    /// * `format`: The texture format of the post-processed frame.
+    /// * `settings`: The settings of the depth of field.
+    ///
+    /// Returns:
+    ///
+    /// The new [DofEffectInner].
+    pub(super) fn new(
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+        format: EffectFormat,
+        settings: &DofSettings,
+    ) -> Self {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &settings.to_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let settings_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &SETTINGS_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let input_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &INPUT_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let depth_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &DEPTH_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let field_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &FIELD_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let prefilter_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[
+                    &settings_bind_group_layout,
+                    &input_bind_group_layout,
+                    &depth_bind_group_layout,
+                ],
+                push_constant_ranges: &[],
+            });
+
+        // The gather, composite and copy passes share a layout, so that
+        // the settings, input and depth stay bound between them.
+        let field_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[
+                    &settings_bind_group_layout,
+                    &input_bind_group_layout,
+                    &depth_bind_group_layout,
+                    &field_bind_group_layout,
+                ],
+                push_constant_ranges: &[],
+            });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let dof_shader = include_composed_wgsl!(device, "shader/dof.wgsl");
+
+        let replace = Some(wgpu::BlendState {
+            color: wgpu::BlendComponent::REPLACE,
+            alpha: wgpu::BlendComponent::REPLACE,
+        });
+        let field_targets = [
+            Some(wgpu::ColorTargetState {
+                format: FIELD_FORMAT,
+                blend: replace,
+                write_mask: wgpu::ColorWrites::ALL,
+            }),
+            Some(wgpu::ColorTargetState {
+                format: FIELD_FORMAT,
+                blend: replace,
+                write_mask: wgpu::ColorWrites::ALL,
+            }),
+        ];
+        let output_targets = [Some(wgpu::ColorTargetState {
+            format: format.output,
+            blend: replace,
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+
+        let create_pipeline =
+            |layout: &wgpu::PipelineLayout,
+             entry_point: &str,
+             targets: &[Option<wgpu::ColorTargetState>]| {
+                device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: None,
+                    layout: Some(layout),
+                    vertex: vertex_shader.state_with_uv(),
+                    fragment: Some(wgpu::FragmentState {
+                        module: &dof_shader,
+                        entry_point,
+                        targets,
+                    }),
+                    primitive: Default::default(),
+                    multisample: Default::default(),
+                    depth_stencil: None,
+                    multiview: None,
+                })
+            };
+        let prefilter_pipeline =
+            create_pipeline(&prefilter_pipeline_layout, "fs_prefilter", &field_targets);
+        let gather_pipeline = create_pipeline(&field_pipeline_layout, "fs_gather", &field_targets);
+        let composite_pipeline =
+            create_pipeline(&field_pipeline_layout, "fs_composite", &output_targets);
+
+        let settings_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: settings_buffer.as_entire_binding(),
+                },
+            ],
+            layout: &settings_bind_group_layout,
+        });
+
+        let prefiltered = create_fields(device, &field_bind_group_layout, size);
+        let gathered = create_fields(device, &field_bind_group_layout, size);
+
+        Self {
+            prefilter_pipeline,
+            gather_pipeline,
+            composite_pipeline,
+            _vertex_shader: vertex_shader,
+            input_bind_group_layout,
+            depth_bind_group_layout,
+            field_bind_group_layout,
+            settings_bind_group,
+            settings: *settings,
+            settings_buffer,
+            bind_groups: BindGroupCache::new(),
+            prefiltered,
+            gathered,
+        }
+    }
+}
+
+impl DofEffectInner {
+    /// Uploads new settings into the uniform buffer.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device, which is not needed to upload the settings.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the depth of field.
+    pub(super) fn set_settings(
+        &mut self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &DofSettings,
+    ) {
+        queue.write_buffer(&self.settings_buffer, 0, &settings.to_bytes());
+        self.settings = *settings;
+    }
+}
+
+impl EffectInner for DofEffectInner {
+    /// Resizes the [DofEffectInner] after creation.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size to which the effect should be resized.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        self.prefiltered = create_fields(device, &self.field_bind_group_layout, size);
+        self.gathered = create_fields(device, &self.field_bind_group_layout, size);
+        self.bind_groups.clear();
+    }
+
+    /// Records the passes of the depth of field which blur `input_view`
+    /// based on the depth of the scene and write the result into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the passes are recorded.
+    /// * `input_view`: The view of the current frame.
+    /// * `inputs`: The additional inputs, which contain the depth of the current frame.
+    /// * `output_view`: The view into which the result is written.
+    ///
+    /// Panics:
+    ///
+    /// If `inputs` contain no depth view.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        // The effect cannot do anything without the depth of the scene, so that
+        // a missing depth texture is a mistake rather than a reason to skip it.
+        let depth_view = inputs.depth_view.expect(
+            "the depth of field effect needs a depth texture, which can be passed using \
+             DofEffect::apply_with_depth, DofFrame::set_depth_texture or DofEffect::set_depth_format",
+        );
+
+        let input_bind_group = self.bind_groups.get_or_insert_with(input_view, || {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(input_view),
+                }],
+                layout: &self.input_bind_group_layout,
+            })
+        });
+
+        let depth_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(depth_view),
+            }],
+            layout: &self.depth_bind_group_layout,
+        });
+
+        // While debugging the CoC, the composite pass does not
+        // read the fields, so that the blur can be skipped.
+        if !self.settings.debug_coc {
+            {
+                let mut rpass = begin_field_pass(encoder, &self.prefiltered);
+                rpass.set_pipeline(&self.prefilter_pipeline);
+                rpass.set_bind_group(0, &self.settings_bind_group, &[]);
+                rpass.set_bind_group(1, input_bind_group, &[]);
+                rpass.set_bind_group(2, &depth_bind_group, &[]);
+                rpass.draw(0..3, 0..1);
+            }
+            {
+                let mut rpass = begin_field_pass(encoder, &self.gathered);
+                rpass.set_pipeline(&self.gather_pipeline);
+                rpass.set_bind_group(0, &self.settings_bind_group, &[]);
+                rpass.set_bind_group(1, input_bind_group, &[]);
+                rpass.set_bind_group(2, &depth_bind_group, &[]);
+                rpass.set_bind_group(3, &self.prefiltered.bind_group, &[]);
+                rpass.draw(0..3, 0..1);
+            }
+        }
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+            label: None,
+        });
+        rpass.set_pipeline(&self.composite_pipeline);
+        rpass.set_bind_group(0, &self.settings_bind_group, &[]);
+        rpass.set_bind_group(1, input_bind_group, &[]);
+        rpass.set_bind_group(2, &depth_bind_group, &[]);
+        rpass.set_bind_group(3, &self.gathered.bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+/// Creates the layout entry of a 2D texture with the given `sample_type`.
+const fn texture_layout_entry(
+    binding: u32,
+    sample_type: wgpu::TextureSampleType,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+/// Creates the textures of the far and near field at half the given `size`.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the field bind group.
+/// * `size`: The size of the frame.
+///
+/// Returns:
+///
+/// The new [Fields].
+fn create_fields(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    size: &wgpu::Extent3d,
+) -> Fields {
+    let half_size = wgpu::Extent3d {
+        width: (size.width / 2).max(1),
+        height: (size.height / 2).max(1),
+        depth_or_array_layers: 1,
+    };
+    let far = create_texture_view(device, &half_size, FIELD_FORMAT);
+    let near = create_texture_view(device, &half_size, FIELD_FORMAT);
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&far),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&near),
+            },
+        ],
+        layout,
+    });
+
+    Fields {
+        far,
+        near,
+        bind_group,
+    }
+}
+
+/// Begins a render pass which writes into both textures of `fields`.
+fn begin_field_pass<'a>(
+    encoder: &'a mut wgpu::CommandEncoder,
+    fields: &'a Fields,
+) -> wgpu::RenderPass<'a> {
+    let attachment = |view| {
+        Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                store: true,
+            },
+        })
+    };
+
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[attachment(&fields.far), attachment(&fields.near)],
+        depth_stencil_attachment: None,
+        label: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shader_matches_bind_group_layouts() {
+        validation::check_entry_point(
+            "dof/shader/dof.wgsl",
+            include_str!("shader/dof.wgsl"),
+            naga::ShaderStage::Fragment,
+            "fs_prefilter",
+            &[
+                &SETTINGS_BIND_GROUP_LAYOUT_ENTRIES,
+                &INPUT_BIND_GROUP_LAYOUT_ENTRIES,
+                &DEPTH_BIND_GROUP_LAYOUT_ENTRIES,
+            ],
+        );
+        for entry_point in ["fs_gather", "fs_composite"] {
+            validation::check_entry_point(
+                "dof/shader/dof.wgsl",
+                include_str!("shader/dof.wgsl"),
+                naga::ShaderStage::Fragment,
+                entry_point,
+                &[
+                    &SETTINGS_BIND_GROUP_LAYOUT_ENTRIES,
+                    &INPUT_BIND_GROUP_LAYOUT_ENTRIES,
+                    &DEPTH_BIND_GROUP_LAYOUT_ENTRIES,
+                    &FIELD_BIND_GROUP_LAYOUT_ENTRIES,
+                ],
+            );
+        }
+    }
+}
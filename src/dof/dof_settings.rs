@@ -0,0 +1,113 @@
+/// The settings of the [crate::dof::DofEffect].
+///
+/// The circle of confusion (CoC) is computed using a thin lens model from the
+/// distance of each pixel, which is reconstructed from the depth of the scene.
+/// Distances are given in the units of the scene, which are assumed to be meters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DofSettings {
+    /// The distance of the plane which is in focus.
+    pub focal_distance: f32,
+
+    /// The focal length of the lens in millimeters.
+    pub focal_length: f32,
+
+    /// The aperture of the lens as f-stop.
+    ///
+    /// Lower values result in a shallower depth of field and a stronger blur.
+    pub aperture: f32,
+
+    /// The height of the sensor in millimeters, which relates the CoC to the height of the frame.
+    ///
+    /// The default of `24` corresponds to a full frame sensor.
+    pub sensor_height: f32,
+
+    /// The largest radius of the CoC in pixels.
+    ///
+    /// This limits the size of the bokeh and therefore the cost of the blur.
+    pub max_coc: f32,
+
+    /// The distance of the near plane of the projection, which is used to linearize the depth.
+    ///
+    /// For a reversed depth buffer, the near and far planes must be swapped.
+    pub z_near: f32,
+
+    /// The distance of the far plane of the projection, which is used to linearize the depth.
+    pub z_far: f32,
+
+    /// Whether the CoC is shown instead of the blurred frame.
+    ///
+    /// Pixels in front of the focal plane are shown in green and pixels behind it in blue,
+    /// whose brightness increases with the size of the CoC. Pixels in focus are black.
+    /// This helps to tune the other settings.
+    pub debug_coc: bool,
+}
+
+impl Default for DofSettings {
+    fn default() -> Self {
+        Self {
+            focal_distance: 10.0,
+            focal_length: 50.0,
+            aperture: 2.8,
+            sensor_height: 24.0,
+            max_coc: 16.0,
+            z_near: 0.1,
+            z_far: 1000.0,
+            debug_coc: false,
+        }
+    }
+}
+
+impl DofSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 32;
+
+    /// Returns the size of the CoC of an infinitely distant pixel relative to the frame height.
+    ///
+    /// The CoC of a pixel at distance `d` is `coc_scale * (1 - focal_distance / d)`,
+    /// which is negative in front of the focal plane and positive behind it.
+    pub(super) fn coc_scale(self) -> f32 {
+        let focal_distance = self.focal_distance * 1000.0;
+        let denominator = self.aperture * (focal_distance - self.focal_length);
+
+        self.focal_length * self.focal_length / denominator.max(f32::EPSILON) / self.sensor_height
+    }
+
+    /// Converts the settings into the layout of the `DofSettings` struct in `dof.wgsl`.
+    pub(super) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.focal_distance.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.coc_scale().to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.max_coc.max(0.0).to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.z_near.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.z_far.to_le_bytes());
+        bytes[20..24].copy_from_slice(&u32::from(self.debug_coc).to_le_bytes());
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coc_follows_the_thin_lens_model() {
+        let settings = DofSettings::default();
+        let coc = |distance: f32| settings.coc_scale() * (1.0 - settings.focal_distance / distance);
+
+        assert_eq!(coc(settings.focal_distance), 0.0);
+        assert!(coc(2.0) < 0.0);
+        assert!(coc(100.0) > 0.0);
+
+        // A 50mm lens at f/2.8 focused at 10m blurs the background into a CoC of
+        // 50^2 / (2.8 * (10000 - 50)) = 0.0897mm on the 24mm sensor.
+        assert!((coc(f32::INFINITY) * settings.sensor_height - 0.0897).abs() < 0.0001);
+
+        // Closing the aperture reduces the blur.
+        let closed = DofSettings {
+            aperture: 16.0,
+            ..settings
+        };
+        assert!(closed.coc_scale() < settings.coc_scale());
+    }
+}
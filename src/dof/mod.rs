@@ -0,0 +1,5 @@
+mod dof_effect;
+pub use dof_effect::{DofEffect, DofFrame};
+mod dof_effect_inner;
+mod dof_settings;
+pub use dof_settings::DofSettings;
@@ -0,0 +1,151 @@
+// A depth of field, which blurs the near and far field separately at half resolution
+// using a gather based bokeh and composites both fields onto the frame.
+// The separation of the fields is based on "Next Generation Post Processing in Call of Duty: Advanced Warfare"
+// by Jorge Jimenez, see: http://www.iryoku.com/next-generation-post-processing-in-call-of-duty-advanced-warfare.
+
+#include "fullscreen.wgsl"
+#include "sampling.wgsl"
+
+struct DofSettings {
+    focal_distance: f32,
+    // The CoC relative to the frame height is `coc_scale * (1 - focal_distance / distance)`.
+    coc_scale: f32,
+    // The largest radius of the CoC in pixels of the frame.
+    max_coc: f32,
+    z_near: f32,
+    z_far: f32,
+    debug_coc: u32,
+};
+
+// The number of rings of the bokeh, where ring `i` consists of `8 * i` samples.
+const RING_COUNT = 3u;
+
+const TAU = 6.283185307;
+
+@group(0) @binding(0) var linear_sampler : sampler;
+@group(0) @binding(1) var<uniform> settings : DofSettings;
+
+@group(1) @binding(0) var input_texture : texture_2d<f32>;
+
+// Bound as a float texture, because loading from depth textures is not supported on every backend.
+@group(2) @binding(0) var depth_texture : texture_2d<f32>;
+
+// The far and near field at half resolution, whose alpha contains the CoC
+// in half resolution pixels, respectively the coverage of the near field after the gather.
+@group(3) @binding(0) var far_texture : texture_2d<f32>;
+@group(3) @binding(1) var near_texture : texture_2d<f32>;
+
+struct FieldOutput {
+    @location(0) far: vec4<f32>,
+    @location(1) near: vec4<f32>,
+};
+
+// Converts a depth value of a perspective projection into the distance from the camera.
+fn linear_depth(depth: f32) -> f32 {
+    return settings.z_near * settings.z_far / (settings.z_far - depth * (settings.z_far - settings.z_near));
+}
+
+// Returns the signed CoC of a pixel of the frame in pixels,
+// which is negative in the near field and positive in the far field.
+fn circle_of_confusion(pixel: vec2<i32>) -> f32 {
+    let view_distance = linear_depth(load_clamped(depth_texture, pixel).r);
+    let height = f32(textureDimensions(input_texture).y);
+    let coc = settings.coc_scale * (1.0 - settings.focal_distance / view_distance) * height;
+    return clamp(coc, -settings.max_coc, settings.max_coc);
+}
+
+// Downsamples the frame into the far and near field at half resolution.
+@fragment
+fn fs_prefilter(in: FullscreenVertex) -> FieldOutput {
+    let pixel = vec2<i32>(in.position.xy) * 2;
+
+    var far_color = vec4<f32>(0.0);
+    var far_coc = 0.0;
+    var near_color = vec3<f32>(0.0);
+    var near_coc = 0.0;
+    for (var i = 0; i < 4; i = i + 1) {
+        let source = pixel + vec2<i32>(i & 1, i >> 1u);
+        let color = load_clamped(input_texture, source).rgb;
+        let coc = circle_of_confusion(source) * 0.5;
+
+        // Pixels in focus do not contribute to the far field,
+        // so that they do not bleed into the blurred background.
+        let far_weight = saturate(coc);
+        far_color = far_color + vec4<f32>(color * far_weight, far_weight);
+        far_coc = far_coc + max(coc, 0.0) * 0.25;
+
+        // The near field is dilated, because it bleeds over the pixels behind it.
+        near_color = near_color + color * 0.25;
+        near_coc = max(near_coc, -coc);
+    }
+
+    var output: FieldOutput;
+    output.far = vec4<f32>(far_color.rgb / max(far_color.a, 0.0001), far_coc);
+    output.near = vec4<f32>(near_color, near_coc);
+    return output;
+}
+
+// Gathers the bokeh of both fields from rings of samples around each pixel.
+// A sample contributes to a pixel if its own CoC covers the distance to the pixel.
+@fragment
+fn fs_gather(in: FullscreenVertex) -> FieldOutput {
+    let texel = texel_size(far_texture);
+    let radius = settings.max_coc * 0.5;
+
+    let center_far = textureSampleLevel(far_texture, linear_sampler, in.uv, 0.0);
+    let center_near = textureSampleLevel(near_texture, linear_sampler, in.uv, 0.0);
+    var far = vec4<f32>(center_far.rgb, 1.0) * saturate(center_far.a);
+    var near = vec4<f32>(center_near.rgb, 1.0) * saturate(center_near.a);
+    var sample_count = 1.0;
+
+    for (var ring = 1u; ring <= RING_COUNT; ring = ring + 1u) {
+        let ring_radius = radius * f32(ring) / f32(RING_COUNT);
+        let ring_samples = 8u * ring;
+        for (var i = 0u; i < ring_samples; i = i + 1u) {
+            // Every other ring is rotated by half a sample, so that the samples do not line up.
+            let angle = TAU * (f32(i) + 0.5 * f32(ring % 2u)) / f32(ring_samples);
+            let uv = in.uv + vec2<f32>(cos(angle), sin(angle)) * ring_radius * texel;
+
+            let far_sample = textureSampleLevel(far_texture, linear_sampler, uv, 0.0);
+            let far_weight = saturate(far_sample.a - ring_radius + 1.0) * step(0.0001, far_sample.a);
+            far = far + vec4<f32>(far_sample.rgb, 1.0) * far_weight;
+
+            let near_sample = textureSampleLevel(near_texture, linear_sampler, uv, 0.0);
+            let near_weight = saturate(near_sample.a - ring_radius + 1.0) * step(0.0001, near_sample.a);
+            near = near + vec4<f32>(near_sample.rgb, 1.0) * near_weight;
+
+            sample_count = sample_count + 1.0;
+        }
+    }
+
+    var output: FieldOutput;
+    output.far = vec4<f32>(far.rgb / max(far.a, 0.0001), center_far.a);
+    // The coverage of the near field is used to blend it over the frame,
+    // so that out of focus objects in front bleed over the sharp background.
+    output.near = vec4<f32>(near.rgb / max(near.a, 0.0001), saturate(2.0 * near.a / sample_count));
+    return output;
+}
+
+// Composites the blurred fields onto the frame at full resolution.
+@fragment
+fn fs_composite(in: FullscreenVertex) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(in.position.xy);
+    let scene = textureLoad(input_texture, pixel, 0);
+    let coc = circle_of_confusion(pixel);
+
+    if (settings.debug_coc != 0u) {
+        let strength = abs(coc) / max(settings.max_coc, 0.0001);
+        if (coc < 0.0) {
+            return vec4<f32>(0.0, strength, 0.0, 1.0);
+        }
+        return vec4<f32>(0.0, 0.0, strength, 1.0);
+    }
+    let far = textureSampleLevel(far_texture, linear_sampler, in.uv, 0.0);
+    let near = textureSampleLevel(near_texture, linear_sampler, in.uv, 0.0);
+
+    // Pixels with a CoC of up to one pixel stay sharp, larger ones fade into the far field.
+    var color = mix(scene.rgb, far.rgb, saturate(coc - 1.0));
+    color = mix(color, near.rgb, near.a);
+
+    return vec4<f32>(color, scene.a);
+}
@@ -0,0 +1,82 @@
+use crate::effect_frame::FrameInputs;
+
+/// An object safe companion of [crate::Effect].
+///
+/// Because [crate::Effect] has an associated frame type it can not be used as a
+/// trait object. A [DynEffect] on the other hand can be stored as `Box<dyn DynEffect>`,
+/// which allows building collections of different effects at runtime,
+/// e.g. for an [crate::EffectChain].
+///
+/// In contrast to [crate::Effect] the input of a [DynEffect] is not
+/// owned by the effect itself, but provided by the caller.
+pub trait DynEffect {
+    /// Resizes the post-processing effect after creation.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size to which the effect should be resized.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d);
+
+    /// Returns whether the post-processing effect is applied.
+    ///
+    /// Disabled effects are skipped by an [crate::EffectChain].
+    fn is_enabled(&self) -> bool;
+
+    /// Enables or disables the post-processing effect.
+    ///
+    /// Disabling an effect releases its GPU resources, which are
+    /// recreated once the effect is enabled again.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `queue`: The command queue which is used to upload resources.
+    /// * `enabled`: Whether the effect should be applied.
+    fn set_enabled(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, enabled: bool);
+
+    /// Records the post-processing effect into the given `encoder`.
+    ///
+    /// A disabled or suspended effect still writes into `output_view`,
+    /// by copying `input_view` unchanged, so that the output is never left stale.
+    /// The input may use a different format than the output.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `input_view`: The view which contains the input of the effect.
+    /// * `output_view`: The view into which the result of the effect is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        output_view: &wgpu::TextureView,
+    );
+
+    /// Records the post-processing effect together with the additional inputs of a frame,
+    /// e.g. the depth of the scene, which is how an [crate::EffectChain] applies its effects.
+    ///
+    /// The inputs are only created by the frames of this crate. The default implementation
+    /// ignores them and calls [DynEffect::apply].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `input_view`: The view which contains the input of the effect.
+    /// * `inputs`: The additional inputs which have been set on the frame.
+    /// * `output_view`: The view into which the result of the effect is written.
+    fn apply_with_inputs(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let _ = inputs;
+        self.apply(device, encoder, input_view, output_view);
+    }
+}
@@ -10,6 +10,10 @@ pub trait Effect {
     /// This should be called when the main surface is resized, so that
     /// no new [Effect] must be created which is slightly faster.
     ///
+    /// An empty size, as reported e.g. for minimized windows, suspends the effect
+    /// until it is resized to a non-empty size. The frames of a suspended effect
+    /// are rendered directly into the output view.
+    ///
     /// Arguments:
     ///
     /// * `device`: The current graphics device.
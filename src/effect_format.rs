@@ -0,0 +1,153 @@
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+use crate::{error, Error};
+
+/// The default intermediate format of effects which operate on HDR frames.
+#[cfg(any(feature = "bloom", feature = "tonemap"))]
+pub(crate) const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// The texture formats used by an [crate::Effect].
+///
+/// The intermediate format is the format of the texture into which a frame is
+/// rendered before the post-processing is applied, while the output format is
+/// the format of the view into which the frame is resolved. Using a separate
+/// intermediate format allows e.g. rendering into `Rgba16Float` to keep the
+/// HDR range, while resolving into a `Bgra8UnormSrgb` swapchain.
+///
+/// Every effect constructor accepts a plain [wgpu::TextureFormat] as well,
+/// in which case the effect chooses its default intermediate format.
+/// This is the output format for most effects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EffectFormat {
+    /// The format of the texture into which the frame is rendered,
+    /// or `None` to use the default of the effect.
+    pub intermediate: Option<wgpu::TextureFormat>,
+
+    /// The format of the view into which the frame is resolved.
+    pub output: wgpu::TextureFormat,
+}
+
+impl EffectFormat {
+    /// Creates a new [EffectFormat] using separate formats.
+    ///
+    /// Arguments:
+    ///
+    /// * `intermediate`: The format of the texture into which the frame is rendered.
+    ///   It must be filterable and usable as a render attachment.
+    /// * `output`: The format of the view into which the frame is resolved.
+    ///
+    /// Returns:
+    ///
+    /// The new [EffectFormat].
+    pub fn new(intermediate: wgpu::TextureFormat, output: wgpu::TextureFormat) -> Self {
+        Self {
+            intermediate: Some(intermediate),
+            output,
+        }
+    }
+
+    /// Returns the intermediate format, or `default` if none has been set.
+    ///
+    /// The format is checked against the same features as [EffectFormat::validate].
+    /// If `device` supports adapter specific format features, these cannot be
+    /// queried here, so that the check is left to `wgpu`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `default`: The intermediate format which is used if none has been set.
+    ///
+    /// Panics:
+    ///
+    /// If the intermediate format is not filterable or cannot be used as a render attachment.
+    pub(crate) fn intermediate_or(
+        &self,
+        device: &wgpu::Device,
+        default: wgpu::TextureFormat,
+    ) -> wgpu::TextureFormat {
+        let intermediate = self.intermediate.unwrap_or(default);
+        if device
+            .features()
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            return intermediate;
+        }
+
+        let features = intermediate.describe().guaranteed_format_features;
+        assert!(
+            features
+                .allowed_usages
+                .contains(wgpu::TextureUsages::RENDER_ATTACHMENT),
+            "The intermediate format {intermediate:?} cannot be used as a render attachment"
+        );
+        assert!(
+            features
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::FILTERABLE),
+            "The intermediate format {intermediate:?} is not filterable"
+        );
+
+        intermediate
+    }
+
+    /// Validates that the formats can be used by an effect on `device`.
+    ///
+    /// Arguments:
+    ///
+    /// * `adapter`: The adapter from which `device` was requested.
+    /// * `device`: The current graphics device.
+    /// * `default`: The intermediate format which is used if none has been set.
+    /// * `sample_count`: The number of samples per pixel of the intermediate texture.
+    #[cfg(any(
+        feature = "bloom",
+        feature = "blur",
+        feature = "dof",
+        feature = "fxaa",
+        feature = "grayscale",
+        feature = "lut",
+        feature = "smaa",
+        feature = "taa",
+        feature = "tonemap"
+    ))]
+    pub(crate) fn validate(
+        &self,
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        default: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), Error> {
+        let intermediate = self.intermediate.unwrap_or(default);
+        error::validate_formats(adapter, device, intermediate, self.output, sample_count)
+    }
+}
+
+impl From<wgpu::TextureFormat> for EffectFormat {
+    fn from(output: wgpu::TextureFormat) -> Self {
+        Self {
+            intermediate: None,
+            output,
+        }
+    }
+}
+
+#[cfg(any(feature = "lut", feature = "tonemap"))]
+/// Returns whether a view of `format` reads and writes linear colors,
+/// which is the case for `*Srgb` and floating point formats.
+pub(crate) fn is_linear(format: wgpu::TextureFormat) -> bool {
+    use wgpu::TextureFormat::*;
+
+    format.describe().srgb
+        || matches!(
+            format,
+            R16Float | Rg16Float | Rgba16Float | R32Float | Rg32Float | Rgba32Float | Rg11b10Float
+        )
+}
@@ -0,0 +1,236 @@
+use std::marker::PhantomData;
+
+#[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+use crate::Error;
+use crate::{blit::LazyBlit, render_target::RenderTarget, Frame};
+
+/// An effect which can be resolved on an [EffectFrame].
+///
+/// The trait cannot be implemented outside of this crate.
+pub trait FrameEffect {
+    /// Returns the target into which the frame is rendered,
+    /// or `None` if the frame is rendered directly into the output view.
+    fn frame_target(&self) -> Option<&RenderTarget>;
+
+    /// Returns the [LazyBlit] which draws images into the frames in [crate::headless],
+    /// so that it is kept together with the effect.
+    fn image_blit(&mut self) -> &mut LazyBlit;
+
+    /// Records the effect on the frame target into `encoder`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `inputs`: The additional inputs which have been set on the frame.
+    /// * `output_view`: The view into which the frame is resolved.
+    fn resolve_frame(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    );
+}
+
+/// An effect which reads the depth of the scene, so that its frames can own or borrow a depth texture.
+#[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+pub trait DepthEffect: FrameEffect {}
+
+/// The inputs of an effect besides the frame itself.
+#[derive(Clone, Copy, Default)]
+pub struct FrameInputs<'a> {
+    /// The depth texture of the scene, which has been passed to the effect.
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    pub(crate) depth_texture: Option<&'a wgpu::Texture>,
+    /// The view of the depth aspect of the depth texture, which is read by the effect.
+    ///
+    /// This is created from `depth_texture` or the depth texture owned by the frames
+    /// before the effect is applied.
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    pub(crate) depth_view: Option<&'a wgpu::TextureView>,
+    /// The view of the motion vectors of the scene.
+    #[cfg(feature = "taa")]
+    pub(crate) velocity_view: Option<&'a wgpu::TextureView>,
+    /// Binds the lifetime while no effect which reads these inputs is enabled.
+    pub(crate) _marker: PhantomData<&'a ()>,
+}
+
+/// The frame into which the scene is rendered before an effect is resolved on it.
+///
+/// Every [crate::Effect] of this crate returns an [EffectFrame] from
+/// [crate::Effect::start_frame], e.g. [crate::grayscale::GrayscaleFrame].
+/// The frame dereferences to the view which should be used as the color attachment,
+/// which is the output view itself if the effect is disabled or suspended.
+///
+/// Dropping the frame resolves the effect, see [Frame::resolve].
+pub struct EffectFrame<'a, E: FrameEffect> {
+    pub(crate) effect: &'a mut E,
+    pub(crate) device: &'a wgpu::Device,
+    pub(crate) queue: &'a wgpu::Queue,
+    pub(crate) output_view: &'a wgpu::TextureView,
+    pub(crate) inputs: FrameInputs<'a>,
+    pub(crate) resolved: bool,
+}
+
+impl<'a, E: FrameEffect> EffectFrame<'a, E> {
+    /// Creates a new [EffectFrame] which resolves `effect` into `output_view`.
+    pub(crate) fn new(
+        effect: &'a mut E,
+        device: &'a wgpu::Device,
+        queue: &'a wgpu::Queue,
+        output_view: &'a wgpu::TextureView,
+    ) -> Self {
+        Self {
+            effect,
+            device,
+            queue,
+            output_view,
+            inputs: FrameInputs::default(),
+            resolved: false,
+        }
+    }
+
+    /// Returns the view into which the multisampled frame must be resolved,
+    /// or `None` if the frame is not multisampled.
+    pub fn resolve_target(&self) -> Option<&wgpu::TextureView> {
+        self.effect.frame_target()?.resolve_target()
+    }
+
+    /// Creates a color attachment which renders into this frame.
+    ///
+    /// If the frame is multisampled, the resolve target is set up,
+    /// so that no manual resolve step is required.
+    ///
+    /// Arguments:
+    ///
+    /// * `ops`: The operations which are performed on the attachment.
+    ///
+    /// Returns:
+    ///
+    /// The new color attachment.
+    pub fn color_attachment(
+        &self,
+        ops: wgpu::Operations<wgpu::Color>,
+    ) -> wgpu::RenderPassColorAttachment<'_> {
+        wgpu::RenderPassColorAttachment {
+            view: self,
+            resolve_target: self.resolve_target(),
+            ops,
+        }
+    }
+
+    /// Records the effect into `encoder` and marks this frame as resolved.
+    fn encode(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.effect
+            .resolve_frame(self.device, encoder, &self.inputs, self.output_view);
+        self.resolved = true;
+    }
+}
+
+#[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+impl<'a, E: DepthEffect> EffectFrame<'a, E> {
+    /// Sets the depth texture of the scene, which is used instead of
+    /// the depth texture owned by the effect.
+    ///
+    /// The effect reads the depth aspect of the texture, so that formats
+    /// with a stencil aspect like [wgpu::TextureFormat::Depth24PlusStencil8] can be used.
+    ///
+    /// Arguments:
+    ///
+    /// * `depth_texture`: The depth texture which was used while rendering into this frame.
+    ///
+    /// Returns:
+    ///
+    /// An [Error] if `depth_texture` is multisampled or cannot be bound,
+    /// because the effect reads it directly.
+    pub fn set_depth_texture(&mut self, depth_texture: &'a wgpu::Texture) -> Result<(), Error> {
+        validate_depth_texture(depth_texture)?;
+        self.inputs.depth_texture = Some(depth_texture);
+
+        Ok(())
+    }
+
+    /// Returns the view of the depth texture owned by the effect,
+    /// which is allocated using its `set_depth_format` method.
+    ///
+    /// Returns:
+    ///
+    /// The depth view or `None` if the frames of the effect have no depth texture.
+    pub fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.effect.frame_target()?.depth_view()
+    }
+}
+
+/// Checks that `depth_texture` can be read by an effect.
+///
+/// Returns:
+///
+/// An [Error] if `depth_texture` is multisampled or was not created
+/// with [wgpu::TextureUsages::TEXTURE_BINDING].
+#[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+pub(crate) fn validate_depth_texture(depth_texture: &wgpu::Texture) -> Result<(), Error> {
+    let sample_count = depth_texture.sample_count();
+    if sample_count != 1 {
+        return Err(Error::MultisampledDepth { sample_count });
+    }
+    if !depth_texture
+        .usage()
+        .contains(wgpu::TextureUsages::TEXTURE_BINDING)
+    {
+        return Err(Error::DepthNotBindable);
+    }
+
+    Ok(())
+}
+
+impl<'a, E: FrameEffect> Frame for EffectFrame<'a, E> {
+    /// Resolves the effect on this [EffectFrame].
+    fn resolve(self) {
+        std::mem::drop(self);
+    }
+
+    /// Resolves the effect on this [EffectFrame] by recording it into `encoder`.
+    ///
+    /// Arguments:
+    ///
+    /// * `encoder`: The command encoder into which the post-processing is recorded.
+    fn resolve_with_encoder(mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.encode(encoder);
+    }
+
+    /// Resolves the effect on this [EffectFrame] into a [wgpu::CommandBuffer].
+    ///
+    /// Returns:
+    ///
+    /// The command buffer containing the post-processing.
+    fn finish(mut self) -> wgpu::CommandBuffer {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        self.encode(&mut encoder);
+        encoder.finish()
+    }
+}
+
+impl<'a, E: FrameEffect> std::ops::Deref for EffectFrame<'a, E> {
+    type Target = wgpu::TextureView;
+    fn deref(&self) -> &Self::Target {
+        match self.effect.frame_target() {
+            None => self.output_view,
+            Some(target) => target.attachment_view(),
+        }
+    }
+}
+
+impl<'a, E: FrameEffect> Drop for EffectFrame<'a, E> {
+    fn drop(&mut self) {
+        if !self.resolved {
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            self.encode(&mut encoder);
+            self.queue.submit(Some(encoder.finish()));
+        }
+    }
+}
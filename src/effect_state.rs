@@ -0,0 +1,1005 @@
+use crate::{
+    blit::{Blit, LazyBlit},
+    effect_frame::FrameInputs,
+    error,
+    render_target::{self, RenderTarget},
+    EffectFormat, Error,
+};
+
+/// The GPU resources of an enabled effect, e.g. its pipelines and internal textures.
+pub(crate) trait EffectInner {
+    /// Recreates the resources which depend on the size of the frame.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size of the frame, which is never empty.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d);
+
+    /// Records the effect into `encoder`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `input_view`: The view which contains the input of the effect.
+    /// * `inputs`: The additional inputs of the effect, e.g. the depth of the scene.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    );
+}
+
+/// The state which is shared by all effects.
+///
+/// The inner effect and the render target of the frames only exist while the
+/// effect is enabled. While the size is empty, the effect is suspended and its
+/// resources are kept at a minimal size.
+pub(crate) struct EffectState<I> {
+    pub(crate) inner: Option<I>,
+    target: Option<RenderTarget>,
+    format: EffectFormat,
+    size: wgpu::Extent3d,
+    sample_count: u32,
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    depth_format: Option<wgpu::TextureFormat>,
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    borrowed_depth_view: Option<(wgpu::Id, wgpu::TextureView)>,
+    passthrough: Option<Blit>,
+    pub(crate) image_blit: LazyBlit,
+}
+
+impl<I: EffectInner> EffectState<I> {
+    /// Creates a new, disabled [EffectState].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The size of the frame which will later be processed.
+    /// * `format`: The formats of the effect.
+    /// * `default`: The intermediate format which is used if `format` does not set one.
+    /// * `sample_count`: The number of samples per pixel of the frames.
+    ///
+    /// Returns:
+    ///
+    /// The new [EffectState].
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+        format: EffectFormat,
+        default: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        Self {
+            inner: None,
+            target: None,
+            format: EffectFormat::new(format.intermediate_or(device, default), format.output),
+            size: *size,
+            sample_count,
+            #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+            depth_format: None,
+            #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+            borrowed_depth_view: None,
+            passthrough: None,
+            image_blit: LazyBlit::default(),
+        }
+    }
+
+    /// Returns the formats of the effect, where the intermediate format is always set.
+    pub(crate) fn format(&self) -> EffectFormat {
+        self.format
+    }
+
+    /// Returns the size of the resources, which is minimal while the effect is suspended.
+    pub(crate) fn allocation_size(&self) -> wgpu::Extent3d {
+        render_target::allocation_size(&self.size)
+    }
+
+    /// Returns the number of samples per pixel of the frames.
+    pub(crate) fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Returns whether the effect is enabled.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Returns whether the effect is suspended, because its size is empty.
+    pub(crate) fn is_suspended(&self) -> bool {
+        render_target::is_empty(&self.size)
+    }
+
+    /// Returns the render target of the frames, if the effect is enabled and not suspended.
+    pub(crate) fn target(&self) -> Option<&RenderTarget> {
+        self.target.as_ref().filter(|_| !self.is_suspended())
+    }
+
+    /// Enables the effect using `inner`, which must have been created
+    /// using the current [EffectState::format] and [EffectState::allocation_size].
+    ///
+    /// The render target of the frames is allocated once the first frame is started.
+    ///
+    /// Arguments:
+    ///
+    /// * `inner`: The resources of the effect.
+    pub(crate) fn enable(&mut self, inner: I) {
+        self.inner = Some(inner);
+        self.passthrough = None;
+    }
+
+    /// Allocates the render target of the frames, if the effect is enabled and
+    /// the target has not been allocated yet.
+    ///
+    /// Effects which are only applied to other views, e.g. in an [crate::EffectChain],
+    /// therefore never allocate a render target.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    pub(crate) fn prepare_target(&mut self, device: &wgpu::Device) {
+        if self.inner.is_none() || self.target.is_some() {
+            return;
+        }
+
+        let size = self.allocation_size();
+        let intermediate = self.format.intermediate.unwrap_or(self.format.output);
+        self.target = Some(RenderTarget::new(
+            device,
+            &size,
+            intermediate,
+            self.sample_count,
+        ));
+        #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+        if let Some(ref mut target) = self.target {
+            target.set_depth_format(device, &size, self.depth_format);
+        }
+    }
+
+    /// Disables the effect, which releases its resources immediately.
+    pub(crate) fn disable(&mut self) {
+        self.inner = None;
+        self.target = None;
+        #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+        {
+            self.borrowed_depth_view = None;
+        }
+    }
+
+    /// Resizes the resources of the effect, if it is enabled.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size of the frame, an empty size suspends the effect.
+    pub(crate) fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        self.size = *size;
+        let size = self.allocation_size();
+        if let Some(ref mut inner) = self.inner {
+            inner.resize(device, &size);
+        }
+        if let Some(ref mut target) = self.target {
+            target.resize(device, &size);
+        }
+    }
+
+    /// Resizes the resources of the effect, returning an error if the size is not supported.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size of the frame, an empty size suspends the effect.
+    pub(crate) fn try_resize(
+        &mut self,
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+    ) -> Result<(), Error> {
+        error::validate_size(device, size)?;
+
+        error::capture(device, || self.resize(device, size))
+    }
+
+    /// Allocates or releases the depth texture owned by the frames.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `depth_format`: The format of the depth texture, `None` releases the depth texture.
+    ///
+    /// Returns:
+    ///
+    /// An [Error] if a depth texture is requested for multisampled frames.
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    pub(crate) fn set_depth_format(
+        &mut self,
+        device: &wgpu::Device,
+        depth_format: Option<wgpu::TextureFormat>,
+    ) -> Result<(), Error> {
+        // The effects bind the depth texture as a single sampled texture,
+        // while multisampled depth textures cannot be resolved.
+        if depth_format.is_some() && self.sample_count != 1 {
+            return Err(Error::MultisampledDepth {
+                sample_count: self.sample_count,
+            });
+        }
+
+        self.depth_format = depth_format;
+        let size = self.allocation_size();
+        if let Some(ref mut target) = self.target {
+            target.set_depth_format(device, &size, depth_format);
+        }
+
+        Ok(())
+    }
+
+    /// Applies the effect to an arbitrary `input_view`, if it is enabled and not suspended.
+    ///
+    /// Otherwise `input_view` is copied into `output_view` unchanged. Without a depth
+    /// texture in `inputs`, the depth view in `inputs` is read, e.g. the one of an [crate::EffectChain].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `input_view`: The view which contains the input of the effect.
+    /// * `inputs`: The additional inputs of the effect.
+    /// * `output_view`: The view into which the result is written.
+    pub(crate) fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let suspended = self.is_suspended();
+        let Some(inner) = self.inner.as_mut().filter(|_| !suspended) else {
+            let format = self.format.output;
+            self.passthrough
+                .get_or_insert_with(|| Blit::new(device, format))
+                .apply(device, encoder, input_view, output_view);
+            return;
+        };
+
+        #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+        let inputs = &FrameInputs {
+            depth_view: match inputs.depth_texture {
+                Some(texture) => Some(depth_aspect_view(&mut self.borrowed_depth_view, texture)),
+                None => inputs.depth_view,
+            },
+            ..*inputs
+        };
+        inner.apply(device, encoder, input_view, inputs, output_view);
+    }
+
+    /// Applies the effect to the render target of the frames, if it is enabled and not suspended.
+    ///
+    /// Without a depth texture in `inputs`, the depth texture owned by the frames is used.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `inputs`: The additional inputs which have been set on the frame.
+    /// * `output_view`: The view into which the frame is resolved.
+    pub(crate) fn resolve(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        if self.is_suspended() {
+            return;
+        }
+        let (Some(inner), Some(target)) = (self.inner.as_mut(), self.target.as_ref()) else {
+            return;
+        };
+
+        #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+        let inputs = &FrameInputs {
+            depth_view: match inputs.depth_texture {
+                Some(texture) => Some(depth_aspect_view(&mut self.borrowed_depth_view, texture)),
+                None => target.sampled_depth_view(),
+            },
+            ..*inputs
+        };
+        inner.apply(device, encoder, target.view(), inputs, output_view);
+    }
+}
+
+/// Returns the view of the depth aspect of a depth texture which is borrowed by an effect.
+///
+/// The view is kept in `cache` and only recreated if another texture is passed,
+/// so that the bind groups which reference it can be cached as well.
+///
+/// Arguments:
+///
+/// * `cache`: The view of the previously borrowed depth texture together with its id.
+/// * `texture`: The borrowed depth texture.
+///
+/// Returns:
+///
+/// The view of the depth aspect of `texture`.
+#[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+fn depth_aspect_view<'a>(
+    cache: &'a mut Option<(wgpu::Id, wgpu::TextureView)>,
+    texture: &wgpu::Texture,
+) -> &'a wgpu::TextureView {
+    let id = texture.global_id();
+    if !matches!(cache, Some((cached_id, _)) if *cached_id == id) {
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            aspect: wgpu::TextureAspect::DepthOnly,
+            ..Default::default()
+        });
+        *cache = Some((id, view));
+    }
+
+    &cache.as_ref().unwrap().1
+}
+
+/// Implements the methods which are shared by all effects, together with
+/// [crate::Effect], [crate::DynEffect] and the frame of the effect.
+///
+/// This includes the constructors, which create the effect using the default or
+/// custom settings and an optional sample count, as well as `settings`,
+/// `set_settings` and `apply`.
+///
+/// The effect must store an [EffectState] in a field named `state`, its settings
+/// in a field named `settings` and provide a `create_inner(&self, device)` method
+/// which creates the inner effect using the current settings. The inner effect
+/// must provide a `set_settings(&mut self, device, queue, settings)` method.
+///
+/// The following optional markers are supported, in this order:
+///
+/// * `queue`: The effect needs the queue to create its resources, which is passed
+///   to the constructors, to `set_enabled` and to `create_inner` as well.
+/// * `hdr`: The frames of the effect use [crate::effect_format::HDR_FORMAT] as
+///   intermediate format by default, instead of the output format.
+/// * `depth`: The effect reads the depth of the scene.
+///
+/// Additional arguments of the constructors can be declared after a semicolon,
+/// each documented by a single doc comment. They are stored in fields of the
+/// same name using [ToOwned].
+///
+/// Arguments:
+///
+/// * `$effect`: The type of the effect.
+/// * `$name`: The name of the effect in the documentation, e.g. `"anti-aliasing"`.
+/// * `$settings`: The type of the settings of the effect.
+macro_rules! impl_effect {
+    ($effect:ident, $name:literal, $settings:ty, queue $(, $marker:ident)* $(; $($args:tt)*)?) => {
+        $crate::effect_state::impl_effect!(
+            @effect $effect, $name, $settings, [queue] $(, $marker)*; $($($args)*)?
+        );
+    };
+    ($effect:ident, $name:literal, $settings:ty $(, $marker:ident)* $(; $($args:tt)*)?) => {
+        $crate::effect_state::impl_effect!(
+            @effect $effect, $name, $settings, [] $(, $marker)*; $($($args)*)?
+        );
+    };
+    (
+        @effect $effect:ident, $name:literal, $settings:ty, [$($queue:ident)?] $(, $marker:ident)*;
+        $(#[doc = $arg_doc:literal] $arg:ident: $arg_ty:ty),* $(,)?
+    ) => {
+        impl $effect {
+            #[doc = concat!("Creates a new [", stringify!($effect), "] using the default [", stringify!($settings), "].")]
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `size`: The size of the frame which will later be processed.
+            /// * `format`: The texture format of the post-processed frame, optionally
+            ///   using a separate intermediate format (see [EffectFormat](crate::EffectFormat)).
+            $(#[doc = concat!("* `", stringify!($arg), "`:", $arg_doc)])*
+            ///
+            /// Returns:
+            ///
+            #[doc = concat!("The new [", stringify!($effect), "].")]
+            pub fn new(
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                size: &wgpu::Extent3d,
+                format: impl Into<$crate::EffectFormat>,
+                $($arg: $arg_ty,)*
+            ) -> Self {
+                Self::with_settings_and_sample_count(
+                    device,
+                    $($queue,)?
+                    size,
+                    format,
+                    $($arg,)*
+                    &<$settings>::default(),
+                    1,
+                )
+            }
+
+            #[doc = concat!("Creates a new [", stringify!($effect), "] using the default [", stringify!($settings), "],")]
+            /// returning an error instead of panicking if the size or format is not supported.
+            ///
+            /// See [Self::try_with_settings_and_sample_count].
+            ///
+            /// Arguments:
+            ///
+            /// * `adapter`: The adapter from which `device` was requested.
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `size`: The size of the frame which will later be processed.
+            /// * `format`: The texture format of the post-processed frame, optionally
+            ///   using a separate intermediate format (see [EffectFormat](crate::EffectFormat)).
+            $(#[doc = concat!("* `", stringify!($arg), "`:", $arg_doc)])*
+            ///
+            /// Returns:
+            ///
+            #[doc = concat!("The new [", stringify!($effect), "] or an [Error](crate::Error) describing why it cannot be created.")]
+            pub fn try_new(
+                adapter: &wgpu::Adapter,
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                size: &wgpu::Extent3d,
+                format: impl Into<$crate::EffectFormat>,
+                $($arg: $arg_ty,)*
+            ) -> Result<Self, $crate::Error> {
+                Self::try_with_settings_and_sample_count(
+                    adapter,
+                    device,
+                    $($queue,)?
+                    size,
+                    format,
+                    $($arg,)*
+                    &<$settings>::default(),
+                    1,
+                )
+            }
+
+            #[doc = concat!("Creates a new [", stringify!($effect), "] using custom [", stringify!($settings), "].")]
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `size`: The size of the frame which will later be processed.
+            /// * `format`: The texture format of the post-processed frame, optionally
+            ///   using a separate intermediate format (see [EffectFormat](crate::EffectFormat)).
+            $(#[doc = concat!("* `", stringify!($arg), "`:", $arg_doc)])*
+            #[doc = concat!("* `settings`: The settings of the ", $name, ".")]
+            ///
+            /// Returns:
+            ///
+            #[doc = concat!("The new [", stringify!($effect), "].")]
+            pub fn with_settings(
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                size: &wgpu::Extent3d,
+                format: impl Into<$crate::EffectFormat>,
+                $($arg: $arg_ty,)*
+                settings: &$settings,
+            ) -> Self {
+                Self::with_settings_and_sample_count(
+                    device,
+                    $($queue,)?
+                    size,
+                    format,
+                    $($arg,)*
+                    settings,
+                    1,
+                )
+            }
+
+            #[doc = concat!("Creates a new [", stringify!($effect), "] using custom [", stringify!($settings), "],")]
+            /// returning an error instead of panicking if the size or format is not supported.
+            ///
+            /// See [Self::try_with_settings_and_sample_count].
+            ///
+            /// Arguments:
+            ///
+            /// * `adapter`: The adapter from which `device` was requested.
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `size`: The size of the frame which will later be processed.
+            /// * `format`: The texture format of the post-processed frame, optionally
+            ///   using a separate intermediate format (see [EffectFormat](crate::EffectFormat)).
+            $(#[doc = concat!("* `", stringify!($arg), "`:", $arg_doc)])*
+            #[doc = concat!("* `settings`: The settings of the ", $name, ".")]
+            ///
+            /// Returns:
+            ///
+            #[doc = concat!("The new [", stringify!($effect), "] or an [Error](crate::Error) describing why it cannot be created.")]
+            pub fn try_with_settings(
+                adapter: &wgpu::Adapter,
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                size: &wgpu::Extent3d,
+                format: impl Into<$crate::EffectFormat>,
+                $($arg: $arg_ty,)*
+                settings: &$settings,
+            ) -> Result<Self, $crate::Error> {
+                Self::try_with_settings_and_sample_count(
+                    adapter,
+                    device,
+                    $($queue,)?
+                    size,
+                    format,
+                    $($arg,)*
+                    settings,
+                    1,
+                )
+            }
+
+            #[doc = concat!("Creates a new multisampled [", stringify!($effect), "] using the default [", stringify!($settings), "].")]
+            ///
+            /// See [Self::with_settings_and_sample_count].
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `size`: The size of the frame which will later be processed.
+            /// * `format`: The texture format of the post-processed frame, optionally
+            ///   using a separate intermediate format (see [EffectFormat](crate::EffectFormat)).
+            $(#[doc = concat!("* `", stringify!($arg), "`:", $arg_doc)])*
+            /// * `sample_count`: The number of samples per pixel of the frame.
+            ///
+            /// Returns:
+            ///
+            #[doc = concat!("The new [", stringify!($effect), "].")]
+            pub fn with_sample_count(
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                size: &wgpu::Extent3d,
+                format: impl Into<$crate::EffectFormat>,
+                $($arg: $arg_ty,)*
+                sample_count: u32,
+            ) -> Self {
+                Self::with_settings_and_sample_count(
+                    device,
+                    $($queue,)?
+                    size,
+                    format,
+                    $($arg,)*
+                    &<$settings>::default(),
+                    sample_count,
+                )
+            }
+
+            #[doc = concat!("Creates a new multisampled [", stringify!($effect), "] using the default [", stringify!($settings), "],")]
+            /// returning an error instead of panicking if the size, format or sample count is not supported.
+            ///
+            /// See [Self::try_with_settings_and_sample_count].
+            ///
+            /// Arguments:
+            ///
+            /// * `adapter`: The adapter from which `device` was requested.
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `size`: The size of the frame which will later be processed.
+            /// * `format`: The texture format of the post-processed frame, optionally
+            ///   using a separate intermediate format (see [EffectFormat](crate::EffectFormat)).
+            $(#[doc = concat!("* `", stringify!($arg), "`:", $arg_doc)])*
+            /// * `sample_count`: The number of samples per pixel of the frame.
+            ///
+            /// Returns:
+            ///
+            #[doc = concat!("The new [", stringify!($effect), "] or an [Error](crate::Error) describing why it cannot be created.")]
+            pub fn try_with_sample_count(
+                adapter: &wgpu::Adapter,
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                size: &wgpu::Extent3d,
+                format: impl Into<$crate::EffectFormat>,
+                $($arg: $arg_ty,)*
+                sample_count: u32,
+            ) -> Result<Self, $crate::Error> {
+                Self::try_with_settings_and_sample_count(
+                    adapter,
+                    device,
+                    $($queue,)?
+                    size,
+                    format,
+                    $($arg,)*
+                    &<$settings>::default(),
+                    sample_count,
+                )
+            }
+
+            #[doc = concat!("Creates a new multisampled [", stringify!($effect), "] using custom [", stringify!($settings), "].")]
+            ///
+            /// The frames of this effect are backed by a multisampled texture which is
+            /// resolved automatically at the end of the render pass, if the color attachment
+            /// is created using [crate::EffectFrame::color_attachment].
+            /// Pipelines which render into the frame must use the same `sample_count`.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `size`: The size of the frame which will later be processed.
+            /// * `format`: The texture format of the post-processed frame, optionally
+            ///   using a separate intermediate format (see [EffectFormat](crate::EffectFormat)).
+            $(#[doc = concat!("* `", stringify!($arg), "`:", $arg_doc)])*
+            #[doc = concat!("* `settings`: The settings of the ", $name, ".")]
+            /// * `sample_count`: The number of samples per pixel of the frame, `1` disables multisampling.
+            ///
+            /// Returns:
+            ///
+            #[doc = concat!("The new [", stringify!($effect), "].")]
+            pub fn with_settings_and_sample_count(
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                size: &wgpu::Extent3d,
+                format: impl Into<$crate::EffectFormat>,
+                $($arg: $arg_ty,)*
+                settings: &$settings,
+                sample_count: u32,
+            ) -> Self {
+                let format = format.into();
+                let default = $crate::effect_state::impl_effect!(@intermediate format $(, $marker)*);
+                let mut effect = Self {
+                    state: $crate::effect_state::EffectState::new(
+                        device,
+                        size,
+                        format,
+                        default,
+                        sample_count,
+                    ),
+                    settings: *settings,
+                    $($arg: ToOwned::to_owned($arg),)*
+                };
+                let inner = effect.create_inner(device $(, $queue)?);
+                effect.state.enable(inner);
+
+                effect
+            }
+
+            #[doc = concat!("Creates a new multisampled [", stringify!($effect), "] using custom [", stringify!($settings), "],")]
+            /// returning an error instead of panicking if the size, format or sample count is not supported.
+            ///
+            /// The size is validated against the limits of the device, the formats and the sample
+            /// count against the features reported by the adapter, before any resources are created.
+            ///
+            /// Arguments:
+            ///
+            /// * `adapter`: The adapter from which `device` was requested.
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `size`: The size of the frame which will later be processed.
+            /// * `format`: The texture format of the post-processed frame, optionally
+            ///   using a separate intermediate format (see [EffectFormat](crate::EffectFormat)).
+            $(#[doc = concat!("* `", stringify!($arg), "`:", $arg_doc)])*
+            #[doc = concat!("* `settings`: The settings of the ", $name, ".")]
+            /// * `sample_count`: The number of samples per pixel of the frame, `1` disables multisampling.
+            ///
+            /// Returns:
+            ///
+            #[doc = concat!("The new [", stringify!($effect), "] or an [Error](crate::Error) describing why it cannot be created.")]
+            // Effects which need the queue and additional arguments exceed the limit.
+            #[allow(clippy::too_many_arguments)]
+            pub fn try_with_settings_and_sample_count(
+                adapter: &wgpu::Adapter,
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                size: &wgpu::Extent3d,
+                format: impl Into<$crate::EffectFormat>,
+                $($arg: $arg_ty,)*
+                settings: &$settings,
+                sample_count: u32,
+            ) -> Result<Self, $crate::Error> {
+                let format = format.into();
+                let default = $crate::effect_state::impl_effect!(@intermediate format $(, $marker)*);
+                $crate::error::try_create(adapter, device, size, &format, default, sample_count, || {
+                    Self::with_settings_and_sample_count(
+                        device,
+                        $($queue,)?
+                        size,
+                        format,
+                        $($arg,)*
+                        settings,
+                        sample_count,
+                    )
+                })
+            }
+
+            #[doc = concat!("Returns the current settings of the ", $name, ".")]
+            pub fn settings(&self) -> &$settings {
+                &self.settings
+            }
+
+            #[doc = concat!("Changes the settings of the ", $name, ".")]
+            ///
+            /// The settings are only uploaded if they differ from the current settings.
+            /// Settings which change the resources of the effect, e.g. the number of
+            /// passes, reallocate them.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `queue`: The queue which is used to upload the settings.
+            #[doc = concat!("* `settings`: The new settings of the ", $name, ".")]
+            pub fn set_settings(
+                &mut self,
+                device: &wgpu::Device,
+                queue: &wgpu::Queue,
+                settings: &$settings,
+            ) {
+                if self.settings == *settings {
+                    return;
+                }
+
+                self.settings = *settings;
+                if let Some(ref mut inner) = self.state.inner {
+                    inner.set_settings(device, queue, settings);
+                }
+            }
+
+            #[doc = concat!("Applies the ", $name, " to an arbitrary `input_view`.")]
+            ///
+            /// In contrast to [crate::Effect::start_frame] the input does not need to be
+            /// rendered into the texture owned by the effect, which avoids a copy
+            /// if the input already exists as a texture.
+            ///
+            /// If the effect is disabled or suspended, `input_view` is copied into `output_view`.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `encoder`: The command encoder into which the effect is recorded.
+            /// * `input_view`: The view which contains the input of the effect.
+            /// * `output_view`: The view into which the result is written.
+            pub fn apply(
+                &mut self,
+                device: &wgpu::Device,
+                encoder: &mut wgpu::CommandEncoder,
+                input_view: &wgpu::TextureView,
+                output_view: &wgpu::TextureView,
+            ) {
+                let inputs = $crate::effect_frame::FrameInputs::default();
+                self.state
+                    .apply(device, encoder, input_view, &inputs, output_view);
+            }
+
+            #[doc = concat!("Resizes the ", $name, ",")]
+            /// returning an error instead of panicking if the size is not supported.
+            ///
+            /// The effect is left unchanged if the size exceeds the limits of the device.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `size`: The new size to which the effect should be resized.
+            pub fn try_resize(
+                &mut self,
+                device: &wgpu::Device,
+                size: &wgpu::Extent3d,
+            ) -> Result<(), $crate::Error> {
+                self.state.try_resize(device, size)
+            }
+
+            #[doc = concat!("Returns whether the ", $name, " is applied.")]
+            pub fn is_enabled(&self) -> bool {
+                self.state.is_enabled()
+            }
+
+            /// Returns whether the effect is suspended, because its size is empty.
+            ///
+            /// This is e.g. the case while the window is minimized. A suspended effect only
+            /// keeps minimal resources and its frames are rendered directly into the output view.
+            /// The resources are restored when the effect is resized to a non-empty size.
+            pub fn is_suspended(&self) -> bool {
+                self.state.is_suspended()
+            }
+
+            /// Returns the number of samples per pixel of the frames of this effect.
+            pub fn sample_count(&self) -> u32 {
+                self.state.sample_count()
+            }
+
+            #[doc = concat!("Enables or disables the ", $name, ".")]
+            ///
+            /// Disabling the effect releases its GPU resources immediately and frames
+            /// are rendered directly into the output view. Enabling it again recreates
+            /// the resources using the current size and settings.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            $(#[doc = concat!("* `", stringify!($queue), "`: The queue which is used to upload the resources of the effect.")])?
+            /// * `enabled`: Whether the effect should be applied.
+            pub fn set_enabled(
+                &mut self,
+                device: &wgpu::Device,
+                $($queue: &wgpu::Queue,)?
+                enabled: bool,
+            ) {
+                if !enabled {
+                    self.state.disable();
+                } else if !self.state.is_enabled() {
+                    let inner = self.create_inner(device $(, $queue)?);
+                    self.state.enable(inner);
+                }
+            }
+        }
+
+        $($crate::effect_state::impl_effect!(@$marker $effect);)*
+
+        impl $crate::Effect for $effect {
+            type Frame<'a> = $crate::EffectFrame<'a, $effect>;
+
+            #[doc = concat!("Resizes the ", $name, " after creation.")]
+            ///
+            /// This should be called when the main surface is resized, so that
+            /// no new [crate::Effect] must be created which is slightly faster.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `size`: The new size to which the effect should be resized.
+            fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+                self.state.resize(device, size);
+            }
+
+            #[doc = concat!("Creates a new frame for the ", $name, ".")]
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `queue`: The command queue where the post-processing should be applied.
+            /// * `output_view`: The view into which the frame will be resolved.
+            ///
+            /// Returns:
+            ///
+            /// The new frame.
+            fn start_frame<'a>(
+                &'a mut self,
+                device: &'a wgpu::Device,
+                queue: &'a wgpu::Queue,
+                output_view: &'a wgpu::TextureView,
+            ) -> Self::Frame<'a> {
+                self.state.prepare_target(device);
+                $crate::EffectFrame::new(self, device, queue, output_view)
+            }
+        }
+
+        impl $crate::DynEffect for $effect {
+            #[doc = concat!("Resizes the ", $name, " after creation.")]
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `size`: The new size to which the effect should be resized.
+            fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+                self.state.resize(device, size);
+            }
+
+            #[doc = concat!("Returns whether the ", $name, " is applied.")]
+            fn is_enabled(&self) -> bool {
+                self.state.is_enabled()
+            }
+
+            #[doc = concat!("Enables or disables the ", $name, ".")]
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `queue`: The command queue which is used to upload resources.
+            /// * `enabled`: Whether the effect should be applied.
+            fn set_enabled(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, enabled: bool) {
+                $crate::effect_state::impl_effect!(
+                    @dyn_set_enabled self, device, queue, enabled, [$($queue)?]
+                );
+            }
+
+            #[doc = concat!("Records the ", $name, " into the given `encoder`.")]
+            ///
+            /// If the effect is disabled or suspended, `input_view` is copied into `output_view`.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `encoder`: The command encoder into which the effect is recorded.
+            /// * `input_view`: The view which contains the input of the effect.
+            /// * `output_view`: The view into which the result is written.
+            fn apply(
+                &mut self,
+                device: &wgpu::Device,
+                encoder: &mut wgpu::CommandEncoder,
+                input_view: &wgpu::TextureView,
+                output_view: &wgpu::TextureView,
+            ) {
+                $effect::apply(self, device, encoder, input_view, output_view);
+            }
+
+            #[doc = concat!("Records the ", $name, " together with the additional inputs of a frame.")]
+            ///
+            /// If the effect is disabled or suspended, `input_view` is copied into `output_view`.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `encoder`: The command encoder into which the effect is recorded.
+            /// * `input_view`: The view which contains the input of the effect.
+            /// * `inputs`: The additional inputs which have been set on the frame.
+            /// * `output_view`: The view into which the result is written.
+            fn apply_with_inputs(
+                &mut self,
+                device: &wgpu::Device,
+                encoder: &mut wgpu::CommandEncoder,
+                input_view: &wgpu::TextureView,
+                inputs: &$crate::effect_frame::FrameInputs<'_>,
+                output_view: &wgpu::TextureView,
+            ) {
+                self.state.apply(device, encoder, input_view, inputs, output_view);
+            }
+        }
+
+        impl $crate::effect_frame::FrameEffect for $effect {
+            fn frame_target(&self) -> Option<&$crate::render_target::RenderTarget> {
+                self.state.target()
+            }
+
+            fn image_blit(&mut self) -> &mut $crate::blit::LazyBlit {
+                &mut self.state.image_blit
+            }
+
+            fn resolve_frame(
+                &mut self,
+                device: &wgpu::Device,
+                encoder: &mut wgpu::CommandEncoder,
+                inputs: &$crate::effect_frame::FrameInputs<'_>,
+                output_view: &wgpu::TextureView,
+            ) {
+                self.state.resolve(device, encoder, inputs, output_view);
+            }
+        }
+    };
+    (@intermediate $format:ident, hdr $(, $marker:ident)*) => {
+        $crate::effect_format::HDR_FORMAT
+    };
+    (@intermediate $format:ident $(, $marker:ident)*) => {
+        $format.output
+    };
+    (@dyn_set_enabled $effect:ident, $device:ident, $queue:ident, $enabled:ident, [queue]) => {
+        $effect.set_enabled($device, $queue, $enabled)
+    };
+    (@dyn_set_enabled $effect:ident, $device:ident, $queue:ident, $enabled:ident, []) => {{
+        let _ = $queue;
+        $effect.set_enabled($device, $enabled)
+    }};
+    (@hdr $effect:ident) => {};
+    (@depth $effect:ident) => {
+        impl $effect {
+            /// Allocates or releases a depth texture owned by the frames of this effect.
+            ///
+            /// The depth texture can be used as the depth attachment while rendering into
+            /// a frame using [crate::EffectFrame::depth_view], so that no separate depth
+            /// texture must be managed. It is resized together with the effect and
+            /// read by the effect, unless the frame borrows another depth texture.
+            ///
+            /// Arguments:
+            ///
+            /// * `device`: The current graphics device.
+            /// * `depth_format`: The format of the depth texture, `None` releases the depth texture.
+            ///
+            /// Returns:
+            ///
+            /// An [Error](crate::Error) if a depth texture is requested while the effect was
+            /// created using a `sample_count` greater than one, because the effect can only
+            /// read single sampled depth textures.
+            pub fn set_depth_format(
+                &mut self,
+                device: &wgpu::Device,
+                depth_format: Option<wgpu::TextureFormat>,
+            ) -> Result<(), $crate::Error> {
+                self.state.set_depth_format(device, depth_format)
+            }
+        }
+
+        impl $crate::effect_frame::DepthEffect for $effect {}
+    };
+}
+pub(crate) use impl_effect;
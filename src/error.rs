@@ -0,0 +1,371 @@
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+use crate::EffectFormat;
+
+/// An error which occurred while creating or resizing an effect, or while passing it a depth texture.
+///
+/// The sizes, formats and depth textures are validated before any resources are created.
+/// Errors which are only detected by `wgpu` itself are returned as [Error::Wgpu].
+#[derive(Debug)]
+pub enum Error {
+    /// The size exceeds the maximum texture dimension of the device.
+    InvalidSize {
+        /// The requested size.
+        size: wgpu::Extent3d,
+        /// The largest supported width and height.
+        max_dimension: u32,
+    },
+    /// The device has not been created with the features required by the format.
+    MissingFeatures {
+        /// The requested format.
+        format: wgpu::TextureFormat,
+        /// The features which are missing.
+        features: wgpu::Features,
+    },
+    /// The format cannot be used as a render attachment or cannot be sampled.
+    NotRenderable(wgpu::TextureFormat),
+    /// The intermediate format cannot be sampled using a filtering sampler.
+    NotFilterable(wgpu::TextureFormat),
+    /// The intermediate format does not support the requested number of samples.
+    UnsupportedSampleCount {
+        /// The requested format.
+        format: wgpu::TextureFormat,
+        /// The requested number of samples per pixel.
+        sample_count: u32,
+    },
+    /// The depth texture is multisampled, while the effects can only read single sampled depth.
+    ///
+    /// This also applies to the depth texture owned by multisampled frames.
+    MultisampledDepth {
+        /// The number of samples per pixel of the depth texture.
+        sample_count: u32,
+    },
+    /// The depth texture was not created using [wgpu::TextureUsages::TEXTURE_BINDING].
+    DepthNotBindable,
+    /// A validation or out of memory error reported by `wgpu`.
+    Wgpu(wgpu::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidSize {
+                size,
+                max_dimension,
+            } => write!(
+                f,
+                "the size {}x{} exceeds the maximum dimension {max_dimension}",
+                size.width, size.height
+            ),
+            Error::MissingFeatures { format, features } => {
+                write!(
+                    f,
+                    "the format {format:?} requires the features {features:?}"
+                )
+            }
+            Error::NotRenderable(format) => {
+                write!(
+                    f,
+                    "the format {format:?} cannot be used as a render attachment"
+                )
+            }
+            Error::NotFilterable(format) => write!(f, "the format {format:?} is not filterable"),
+            Error::UnsupportedSampleCount {
+                format,
+                sample_count,
+            } => write!(
+                f,
+                "the format {format:?} does not support {sample_count} samples"
+            ),
+            Error::MultisampledDepth { sample_count } => write!(
+                f,
+                "the depth texture with {sample_count} samples cannot be read by the effect"
+            ),
+            Error::DepthNotBindable => write!(
+                f,
+                "the depth texture must be created with TextureUsages::TEXTURE_BINDING"
+            ),
+            Error::Wgpu(error) => write!(f, "wgpu error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Wgpu(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<wgpu::Error> for Error {
+    fn from(error: wgpu::Error) -> Self {
+        Error::Wgpu(error)
+    }
+}
+
+/// Validates that textures of `size` can be created on `device`.
+///
+/// Empty sizes are valid, because they suspend the effect.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `size`: The size of the frame which will be processed.
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+pub(crate) fn validate_size(device: &wgpu::Device, size: &wgpu::Extent3d) -> Result<(), Error> {
+    let max_dimension = device.limits().max_texture_dimension_2d;
+    if size.width <= max_dimension && size.height <= max_dimension {
+        Ok(())
+    } else {
+        Err(Error::InvalidSize {
+            size: *size,
+            max_dimension,
+        })
+    }
+}
+
+/// Validates that the `intermediate` format can be rendered into and sampled by an effect,
+/// and that the `output` format can be rendered into.
+///
+/// Arguments:
+///
+/// * `adapter`: The adapter of the device, which reports the supported format features.
+/// * `device`: The current graphics device.
+/// * `intermediate`: The format of the texture into which a frame is rendered.
+/// * `output`: The format of the view into which a frame is resolved.
+/// * `sample_count`: The number of samples per pixel of the intermediate texture.
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+pub(crate) fn validate_formats(
+    adapter: &wgpu::Adapter,
+    device: &wgpu::Device,
+    intermediate: wgpu::TextureFormat,
+    output: wgpu::TextureFormat,
+    sample_count: u32,
+) -> Result<(), Error> {
+    let features = format_features(adapter, device, intermediate)?;
+    let usages = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+    if !features.allowed_usages.contains(usages) {
+        return Err(Error::NotRenderable(intermediate));
+    }
+    if !features
+        .flags
+        .contains(wgpu::TextureFormatFeatureFlags::FILTERABLE)
+    {
+        return Err(Error::NotFilterable(intermediate));
+    }
+    let resolvable = sample_count == 1
+        || features
+            .flags
+            .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE);
+    if !features.flags.sample_count_supported(sample_count) || !resolvable {
+        return Err(Error::UnsupportedSampleCount {
+            format: intermediate,
+            sample_count,
+        });
+    }
+
+    let features = format_features(adapter, device, output)?;
+    if !features
+        .allowed_usages
+        .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+    {
+        return Err(Error::NotRenderable(output));
+    }
+
+    Ok(())
+}
+
+/// Validates the size and formats of a new effect and creates it inside error scopes.
+///
+/// This is shared by the `try_new` constructors of all effects.
+///
+/// Arguments:
+///
+/// * `adapter`: The adapter of the device, which reports the supported format features.
+/// * `device`: The current graphics device.
+/// * `size`: The size of the frame which will be processed.
+/// * `format`: The formats of the effect.
+/// * `default`: The intermediate format of the effect which is used if `format` does not set one.
+/// * `sample_count`: The number of samples per pixel of the frames.
+/// * `create`: Creates the effect, after the arguments have been validated.
+///
+/// Returns:
+///
+/// The new effect or the first error which was found.
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+pub(crate) fn try_create<T>(
+    adapter: &wgpu::Adapter,
+    device: &wgpu::Device,
+    size: &wgpu::Extent3d,
+    format: &EffectFormat,
+    default: wgpu::TextureFormat,
+    sample_count: u32,
+    create: impl FnOnce() -> T,
+) -> Result<T, Error> {
+    validate_size(device, size)?;
+    format.validate(adapter, device, default, sample_count)?;
+
+    capture(device, create)
+}
+
+/// Runs `f` inside validation and out of memory error scopes.
+///
+/// On native platforms the errors are reported immediately. On the web they are
+/// only reported asynchronously, so that `f` is considered successful.
+///
+/// Arguments:
+///
+/// * `device`: The device on which the resources are created.
+/// * `f`: The function which creates the resources.
+///
+/// Returns:
+///
+/// The result of `f` or the first error reported by `wgpu`.
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+pub(crate) fn capture<T>(device: &wgpu::Device, f: impl FnOnce() -> T) -> Result<T, Error> {
+    device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let value = f();
+    let validation = poll_once(device.pop_error_scope()).flatten();
+    let out_of_memory = poll_once(device.pop_error_scope()).flatten();
+
+    match validation.or(out_of_memory) {
+        Some(error) => Err(Error::Wgpu(error)),
+        None => Ok(value),
+    }
+}
+
+/// Returns the features of `format` which can be used on `device`.
+///
+/// Adapter specific features are only available if the device has been created
+/// using [wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES].
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+fn format_features(
+    adapter: &wgpu::Adapter,
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+) -> Result<wgpu::TextureFormatFeatures, Error> {
+    let info = format.describe();
+    let missing = info.required_features - device.features();
+    if !missing.is_empty() {
+        return Err(Error::MissingFeatures {
+            format,
+            features: missing,
+        });
+    }
+
+    if device
+        .features()
+        .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    {
+        Ok(adapter.get_texture_format_features(format))
+    } else {
+        Ok(info.guaranteed_format_features)
+    }
+}
+
+/// Polls `future` a single time, which is sufficient for futures which are ready immediately.
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+fn poll_once<F: Future>(future: F) -> Option<F::Output> {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(std::ptr::null(), &VTABLE),
+        |_| {},
+        |_| {},
+        |_| {},
+    );
+
+    // SAFETY: The waker does nothing, so that the vtable functions uphold its contract.
+    let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+    match pin!(future).poll(&mut Context::from_waker(&waker)) {
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
@@ -1,5 +1,26 @@
 /// The frame on which the post-processing will take place.
 pub trait Frame {
     /// Resolves the [crate::Effect] on the frame.
+    ///
+    /// The post-processing is recorded into a new command encoder
+    /// which is submitted to the queue immediately.
     fn resolve(self);
+
+    /// Resolves the [crate::Effect] on the frame by recording it into an existing `encoder`.
+    ///
+    /// Nothing is submitted to the queue, so the caller is responsible for
+    /// submitting the `encoder` after all remaining passes have been recorded.
+    ///
+    /// Arguments:
+    ///
+    /// * `encoder`: The command encoder into which the post-processing is recorded.
+    fn resolve_with_encoder(self, encoder: &mut wgpu::CommandEncoder);
+
+    /// Resolves the [crate::Effect] on the frame into a [wgpu::CommandBuffer]
+    /// without submitting it to the queue.
+    ///
+    /// Returns:
+    ///
+    /// The command buffer containing the post-processing.
+    fn finish(self) -> wgpu::CommandBuffer;
 }
@@ -0,0 +1,24 @@
+use crate::{
+    effect_state::{impl_effect, EffectState},
+    EffectFrame,
+};
+
+use super::{fxaa_effect_inner::FxaaEffectInner, FxaaSettings};
+
+/// Smooths jagged edges using fast approximate anti-aliasing (FXAA).
+pub struct FxaaEffect {
+    pub(super) state: EffectState<FxaaEffectInner>,
+    pub(super) settings: FxaaSettings,
+}
+
+/// The frame into which the scene is rendered before the [FxaaEffect] is resolved.
+pub type FxaaFrame<'a> = EffectFrame<'a, FxaaEffect>;
+
+impl FxaaEffect {
+    /// Creates the resources of the effect using the current format and settings.
+    fn create_inner(&self, device: &wgpu::Device) -> FxaaEffectInner {
+        FxaaEffectInner::new(device, self.state.format(), &self.settings)
+    }
+}
+
+impl_effect!(FxaaEffect, "anti-aliasing", FxaaSettings);
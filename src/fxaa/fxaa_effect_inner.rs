@@ -0,0 +1,280 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::FxaaSettings;
+
+/// The entries of the bind group layout, which match the bindings of `shader/fxaa.wgsl`.
+const BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 3] = [
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 2,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(FxaaSettings::SIZE as u64),
+        },
+        count: None,
+    },
+];
+
+/// Wraps the internal fields and implementation
+/// of a [crate::fxaa::FxaaEffect].
+pub(super) struct FxaaEffectInner {
+    pub(super) pipeline: wgpu::RenderPipeline,
+    pub(super) _vertex_shader: VertexShader,
+    pub(super) bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) sampler: wgpu::Sampler,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) bind_groups: BindGroupCache,
+}
+
+impl FxaaEffectInner {
+    /// Creates a new [FxaaEffectInner].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `format`: The texture format of the post-processed frame.
+    /// * `settings`: The settings of the anti-aliasing.
+    ///
+    /// Returns:
+    ///
+    /// The new [FxaaEffectInner].
+    pub(super) fn new(
+        device: &wgpu::Device,
+        format: EffectFormat,
+        settings: &FxaaSettings,
+    ) -> Self {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &settings.to_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &BIND_GROUP_LAYOUT_ENTRIES,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let fxaa_shader = include_composed_wgsl!(device, "shader/fxaa.wgsl");
+
+        let fxaa_shader_vert = vertex_shader.state();
+
+        let fxaa_shader_frag = wgpu::FragmentState {
+            module: &fxaa_shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: format.output,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent::REPLACE,
+                    alpha: wgpu::BlendComponent::REPLACE,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        };
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: fxaa_shader_vert,
+            fragment: Some(fxaa_shader_frag),
+            primitive: Default::default(),
+            multisample: Default::default(),
+            depth_stencil: None,
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            _vertex_shader: vertex_shader,
+            bind_group_layout,
+            sampler,
+            settings_buffer,
+            bind_groups: BindGroupCache::new(),
+        }
+    }
+
+    /// Uploads new settings into the uniform buffer.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device, which is not needed to upload the settings.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the anti-aliasing.
+    pub(super) fn set_settings(
+        &self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &FxaaSettings,
+    ) {
+        queue.write_buffer(&self.settings_buffer, 0, &settings.to_bytes());
+    }
+}
+
+impl EffectInner for FxaaEffectInner {
+    /// Releases the bind groups of the previous frame targets, because
+    /// the anti-aliasing pass itself does not depend on the size of the frame.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device.
+    /// * `_size`: The new size of the frame.
+    fn resize(&mut self, _device: &wgpu::Device, _size: &wgpu::Extent3d) {
+        self.bind_groups.clear();
+    }
+
+    /// Records the anti-aliasing pass which reads from `input_view`
+    /// and writes into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the pass is recorded.
+    /// * `input_view`: The view which will be anti-aliased.
+    /// * `_inputs`: The additional inputs, which are not used by the anti-aliasing pass.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        _inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let bind_group = self.bind_groups.get_or_insert_with(input_view, || {
+            create_bind_group(
+                device,
+                &self.bind_group_layout,
+                input_view,
+                &self.sampler,
+                &self.settings_buffer,
+            )
+        });
+
+        render(encoder, &self.pipeline, bind_group, output_view);
+    }
+}
+
+/// Creates the bind group which binds `input_view` as the input texture of the anti-aliasing pass.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the bind group of the anti-aliasing pipeline.
+/// * `input_view`: The view which will be anti-aliased.
+/// * `sampler`: The linear sampler used to sample `input_view`.
+/// * `settings_buffer`: The uniform buffer containing the [FxaaSettings].
+///
+/// Returns:
+///
+/// The new bind group.
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    input_view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    settings_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(input_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: settings_buffer.as_entire_binding(),
+            },
+        ],
+        layout,
+    })
+}
+
+/// Records the anti-aliasing pass using an already created `bind_group`.
+///
+/// Arguments:
+///
+/// * `encoder`: The command encoder into which the pass is recorded.
+/// * `pipeline`: The anti-aliasing pipeline.
+/// * `bind_group`: The bind group which contains the input texture.
+/// * `output_view`: The view into which the result is written.
+fn render(
+    encoder: &mut wgpu::CommandEncoder,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+    output_view: &wgpu::TextureView,
+) {
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: output_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: true,
+            },
+        })],
+        depth_stencil_attachment: None,
+        label: None,
+    });
+    rpass.set_pipeline(pipeline);
+    rpass.set_bind_group(0, bind_group, &[]);
+    rpass.draw(0..3, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shader_matches_bind_group_layout() {
+        validation::check_entry_point(
+            "fxaa/shader/fxaa.wgsl",
+            include_str!("shader/fxaa.wgsl"),
+            naga::ShaderStage::Fragment,
+            "fs_main",
+            &[&BIND_GROUP_LAYOUT_ENTRIES],
+        );
+    }
+}
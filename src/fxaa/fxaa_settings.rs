@@ -0,0 +1,90 @@
+/// The quality presets of the [crate::fxaa::FxaaEffect].
+///
+/// The presets differ in the number of steps which are used to search for
+/// the end of an edge. More steps allow handling longer edges correctly,
+/// but are more expensive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FxaaQuality {
+    /// Uses 5 search steps, similar to FXAA preset 12.
+    Low,
+    /// Uses 8 search steps, similar to FXAA preset 25.
+    #[default]
+    Medium,
+    /// Uses 12 search steps, similar to FXAA preset 39.
+    High,
+}
+
+impl FxaaQuality {
+    /// Returns the distances (in pixels) of the individual edge search steps.
+    pub(super) fn search_steps(&self) -> &'static [f32] {
+        match self {
+            FxaaQuality::Low => &[1.0, 1.5, 2.0, 4.0, 12.0],
+            FxaaQuality::Medium => &[1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 4.0, 8.0],
+            FxaaQuality::High => &[1.0, 1.0, 1.0, 1.0, 1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 4.0, 8.0],
+        }
+    }
+}
+
+/// The settings of the [crate::fxaa::FxaaEffect].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FxaaSettings {
+    /// The number of edge search steps.
+    pub quality: FxaaQuality,
+
+    /// The amount of subpixel aliasing removal in range `0..=1`.
+    ///
+    /// Higher values result in a softer image.
+    pub subpixel: f32,
+
+    /// The minimum amount of local contrast relative to the
+    /// local maximum luma which is required to apply the effect.
+    ///
+    /// Typical values are in range `0.063..=0.333`, where lower values are
+    /// slower, but also process edges with less contrast.
+    pub edge_threshold: f32,
+
+    /// The absolute minimum amount of local contrast required to apply the effect.
+    ///
+    /// This avoids processing dark areas. Typical values are in range `0.0312..=0.0833`.
+    pub edge_threshold_min: f32,
+
+    /// Whether the luma of the scene is already stored in the alpha channel of the input.
+    ///
+    /// Otherwise the luma is computed from the color of the input.
+    pub luma_in_alpha: bool,
+}
+
+impl Default for FxaaSettings {
+    fn default() -> Self {
+        Self {
+            quality: FxaaQuality::default(),
+            subpixel: 0.75,
+            edge_threshold: 0.166,
+            edge_threshold_min: 0.0833,
+            luma_in_alpha: false,
+        }
+    }
+}
+
+impl FxaaSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 80;
+
+    /// Converts the settings into the layout of the `FxaaSettings` struct in `fxaa.wgsl`.
+    pub(super) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let steps = self.quality.search_steps();
+
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.subpixel.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.edge_threshold.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.edge_threshold_min.to_le_bytes());
+        bytes[12..16].copy_from_slice(&u32::from(self.luma_in_alpha).to_le_bytes());
+        bytes[16..20].copy_from_slice(&(steps.len() as u32).to_le_bytes());
+        for (i, step) in steps.iter().enumerate() {
+            let offset = 32 + i * 4;
+            bytes[offset..offset + 4].copy_from_slice(&step.to_le_bytes());
+        }
+
+        bytes
+    }
+}
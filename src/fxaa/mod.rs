@@ -0,0 +1,5 @@
+mod fxaa_effect;
+pub use fxaa_effect::{FxaaEffect, FxaaFrame};
+mod fxaa_effect_inner;
+mod fxaa_settings;
+pub use fxaa_settings::{FxaaQuality, FxaaSettings};
@@ -0,0 +1,171 @@
+// A port of the quality variant of FXAA 3.11 by Timothy Lottes.
+// See: https://github.com/NVIDIAGameWorks/GraphicsSamples/blob/master/samples/es3-kepler/FXAA/FXAA3_11.h.
+
+#include "color.wgsl"
+#include "sampling.wgsl"
+
+struct FxaaSettings {
+    subpixel: f32,
+    edge_threshold: f32,
+    edge_threshold_min: f32,
+    luma_in_alpha: u32,
+    step_count: u32,
+    // The distances of the individual edge search steps, packed into vectors.
+    steps: array<vec4<f32>, 3>,
+};
+
+@group(0) @binding(0) var input_texture : texture_2d<f32>;
+@group(0) @binding(1) var input_sampler : sampler;
+@group(0) @binding(2) var<uniform> settings : FxaaSettings;
+
+fn luma(color: vec4<f32>) -> f32 {
+    if (settings.luma_in_alpha != 0u) {
+        return color.a;
+    }
+
+    return luma_rec601(color.rgb);
+}
+
+fn sample_luma(uv: vec2<f32>) -> f32 {
+    return luma(textureSampleLevel(input_texture, input_sampler, uv, 0.0));
+}
+
+fn search_step(index: u32) -> f32 {
+    return settings.steps[index / 4u][index % 4u];
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let texel = texel_size(input_texture);
+    let uv = pos.xy * texel;
+
+    let color_m = textureSampleLevel(input_texture, input_sampler, uv, 0.0);
+    let luma_m = luma(color_m);
+    let luma_n = sample_luma(uv + vec2<f32>(0.0, -1.0) * texel);
+    let luma_s = sample_luma(uv + vec2<f32>(0.0, 1.0) * texel);
+    let luma_w = sample_luma(uv + vec2<f32>(-1.0, 0.0) * texel);
+    let luma_e = sample_luma(uv + vec2<f32>(1.0, 0.0) * texel);
+
+    let range_max = max(max(max(luma_n, luma_s), max(luma_w, luma_e)), luma_m);
+    let range_min = min(min(min(luma_n, luma_s), min(luma_w, luma_e)), luma_m);
+    let range = range_max - range_min;
+
+    // Skip all pixels which are not part of a visible edge.
+    if (range < max(settings.edge_threshold_min, range_max * settings.edge_threshold)) {
+        return color_m;
+    }
+
+    let luma_nw = sample_luma(uv + vec2<f32>(-1.0, -1.0) * texel);
+    let luma_ne = sample_luma(uv + vec2<f32>(1.0, -1.0) * texel);
+    let luma_sw = sample_luma(uv + vec2<f32>(-1.0, 1.0) * texel);
+    let luma_se = sample_luma(uv + vec2<f32>(1.0, 1.0) * texel);
+
+    // Determine whether the edge is horizontal or vertical.
+    let luma_ns = luma_n + luma_s;
+    let luma_we = luma_w + luma_e;
+    let luma_nwne = luma_nw + luma_ne;
+    let luma_swse = luma_sw + luma_se;
+    let luma_nwsw = luma_nw + luma_sw;
+    let luma_nese = luma_ne + luma_se;
+
+    let edge_horz = abs(-2.0 * luma_w + luma_nwsw)
+        + abs(-2.0 * luma_m + luma_ns) * 2.0
+        + abs(-2.0 * luma_e + luma_nese);
+    let edge_vert = abs(-2.0 * luma_n + luma_nwne)
+        + abs(-2.0 * luma_m + luma_we) * 2.0
+        + abs(-2.0 * luma_s + luma_swse);
+    let horz_span = edge_horz >= edge_vert;
+
+    // Select the neighbors perpendicular to the edge.
+    var luma_1 = luma_w;
+    var luma_2 = luma_e;
+    var length_sign = texel.x;
+    if (horz_span) {
+        luma_1 = luma_n;
+        luma_2 = luma_s;
+        length_sign = texel.y;
+    }
+
+    let gradient_1 = luma_1 - luma_m;
+    let gradient_2 = luma_2 - luma_m;
+    let pair_1 = abs(gradient_1) >= abs(gradient_2);
+    let gradient_scaled = max(abs(gradient_1), abs(gradient_2)) * 0.25;
+
+    var luma_local_average = (luma_2 + luma_m) * 0.5;
+    if (pair_1) {
+        length_sign = -length_sign;
+        luma_local_average = (luma_1 + luma_m) * 0.5;
+    }
+    let luma_m_lt_zero = luma_m - luma_local_average < 0.0;
+
+    // Move half a pixel towards the edge and search along it in both directions.
+    var pos_b = uv;
+    var offset = vec2<f32>(0.0, texel.y);
+    if (horz_span) {
+        pos_b.y += length_sign * 0.5;
+        offset = vec2<f32>(texel.x, 0.0);
+    } else {
+        pos_b.x += length_sign * 0.5;
+    }
+
+    var pos_n = pos_b - offset * search_step(0u);
+    var pos_p = pos_b + offset * search_step(0u);
+    var luma_end_n = 0.0;
+    var luma_end_p = 0.0;
+    var done_n = false;
+    var done_p = false;
+
+    for (var i = 1u; i < settings.step_count; i += 1u) {
+        if (!done_n) {
+            luma_end_n = sample_luma(pos_n) - luma_local_average;
+            done_n = abs(luma_end_n) >= gradient_scaled;
+            if (!done_n) {
+                pos_n -= offset * search_step(i);
+            }
+        }
+        if (!done_p) {
+            luma_end_p = sample_luma(pos_p) - luma_local_average;
+            done_p = abs(luma_end_p) >= gradient_scaled;
+            if (!done_p) {
+                pos_p += offset * search_step(i);
+            }
+        }
+        if (done_n && done_p) {
+            break;
+        }
+    }
+
+    // Compute the offset towards the closest end of the edge.
+    var dst_n = uv.y - pos_n.y;
+    var dst_p = pos_p.y - uv.y;
+    if (horz_span) {
+        dst_n = uv.x - pos_n.x;
+        dst_p = pos_p.x - uv.x;
+    }
+
+    let direction_n = dst_n < dst_p;
+    let dst = min(dst_n, dst_p);
+    let span_length = dst_n + dst_p;
+    let good_span_n = (luma_end_n < 0.0) != luma_m_lt_zero;
+    let good_span_p = (luma_end_p < 0.0) != luma_m_lt_zero;
+    let good_span = select(good_span_p, good_span_n, direction_n);
+    let pixel_offset = select(0.0, 0.5 - dst / span_length, good_span);
+
+    // Estimate the subpixel aliasing from the average of the neighborhood.
+    let subpix_a = (luma_ns + luma_we) * 2.0 + luma_nwsw + luma_nese;
+    let subpix_b = clamp(abs(subpix_a / 12.0 - luma_m) / range, 0.0, 1.0);
+    let subpix_c = (-2.0 * subpix_b + 3.0) * subpix_b * subpix_b;
+    let subpix_offset = subpix_c * subpix_c * settings.subpixel;
+
+    let final_offset = max(pixel_offset, subpix_offset) * length_sign;
+    var final_uv = uv;
+    if (horz_span) {
+        final_uv.y += final_offset;
+    } else {
+        final_uv.x += final_offset;
+    }
+
+    let color = textureSampleLevel(input_texture, input_sampler, final_uv, 0.0);
+
+    return vec4<f32>(color.rgb, color_m.a);
+}
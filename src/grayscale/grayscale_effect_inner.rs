@@ -1,13 +1,47 @@
-use wgpu::include_wgsl;
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::GrayscaleSettings;
+
+/// The entries of the bind group layout, which match the bindings of `shader/grayscale.wgsl`.
+const BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 2] = [
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(GrayscaleSettings::SIZE as u64),
+        },
+        count: None,
+    },
+];
 
 /// Wraps the internal fields and implementation
 /// of a [crate::grayscale::GrayscaleEffect].
 pub(super) struct GrayscaleEffectInner {
     pub(super) pipeline: wgpu::RenderPipeline,
-    pub(super) bind_group: wgpu::BindGroup,
-    pub(super) target: wgpu::TextureView,
-    pub(super) format: wgpu::TextureFormat,
-    pub(super) size: wgpu::Extent3d,
+    pub(super) _vertex_shader: VertexShader,
+    pub(super) bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) bind_groups: BindGroupCache,
 }
 
 impl GrayscaleEffectInner {
@@ -16,56 +50,26 @@ impl GrayscaleEffectInner {
     /// Arguments:
     ///
     /// * `device`: The current graphics device.
-    /// * `size`: The size of the frame which will later be processed.
     /// * `format`: The texture format of the post-processed frame.
+    /// * `settings`: The settings of the grayscale effect.
     ///
     /// Returns:
     ///
     /// The new [GrayscaleEffectInner].
     pub(super) fn new(
         device: &wgpu::Device,
-        size: &wgpu::Extent3d,
-        format: wgpu::TextureFormat,
+        format: EffectFormat,
+        settings: &GrayscaleSettings,
     ) -> Self {
-        let texture_desc = wgpu::TextureDescriptor {
-            size: *size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: None,
-            view_formats: &[],
-        };
-
-        let target = device
-            .create_texture(&wgpu::TextureDescriptor {
-                format,
-                ..texture_desc
-            })
-            .create_view(&wgpu::TextureViewDescriptor::default());
-
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: None,
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Texture {
-                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    view_dimension: wgpu::TextureViewDimension::D2,
-                    multisampled: false,
-                },
-                count: None,
-            }],
+            contents: &settings.to_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: None,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: wgpu::BindingResource::TextureView(&target),
-            }],
-            layout: &bind_group_layout,
+            entries: &BIND_GROUP_LAYOUT_ENTRIES,
         });
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -74,19 +78,16 @@ impl GrayscaleEffectInner {
             push_constant_ranges: &[],
         });
 
-        let grayscale_shader = device.create_shader_module(include_wgsl!("shader/grayscale.wgsl"));
+        let vertex_shader = VertexShader::fullscreen(device);
+        let grayscale_shader = include_composed_wgsl!(device, "shader/grayscale.wgsl");
 
-        let grayscale_shader_vert = wgpu::VertexState {
-            module: &grayscale_shader,
-            entry_point: "vs_main",
-            buffers: &[],
-        };
+        let grayscale_shader_vert = vertex_shader.state();
 
         let grayscale_shader_frag = wgpu::FragmentState {
             module: &grayscale_shader,
             entry_point: "fs_main",
             targets: &[Some(wgpu::ColorTargetState {
-                format,
+                format: format.output,
                 blend: Some(wgpu::BlendState {
                     color: wgpu::BlendComponent::REPLACE,
                     alpha: wgpu::BlendComponent::REPLACE,
@@ -108,64 +109,184 @@ impl GrayscaleEffectInner {
 
         Self {
             pipeline,
-            bind_group,
-            target,
-            format,
-            size: *size,
+            _vertex_shader: vertex_shader,
+            bind_group_layout,
+            settings_buffer,
+            bind_groups: BindGroupCache::new(),
         }
     }
 
-    /// Resizes the [GrayscaleEffectInner] after creation.
+    /// Uploads new settings into the uniform buffer.
     ///
-    /// This should be called when the main surface is resized, so that
-    /// no new [GrayscaleEffectInner] must be created which is slightly faster.
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device, which is not needed to upload the settings.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the grayscale effect.
+    pub(super) fn set_settings(
+        &self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &GrayscaleSettings,
+    ) {
+        queue.write_buffer(&self.settings_buffer, 0, &settings.to_bytes());
+    }
+}
+
+impl EffectInner for GrayscaleEffectInner {
+    /// Releases the bind groups of the previous frame targets, because
+    /// the grayscale pass itself does not depend on the size of the frame.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device.
+    /// * `_size`: The new size of the frame.
+    fn resize(&mut self, _device: &wgpu::Device, _size: &wgpu::Extent3d) {
+        self.bind_groups.clear();
+    }
+
+    /// Records the grayscale pass which reads from `input_view`
+    /// and writes into `output_view`.
+    ///
+    /// The bind group for `input_view` is cached, so that it only
+    /// needs to be created once for every input.
     ///
     /// Arguments:
     ///
     /// * `device`: The current graphics device.
-    /// * `size`: The new size to which the effect should be resized.
-    pub(crate) fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
-        self.size = *size;
-
-        let texture_desc = wgpu::TextureDescriptor {
-            size: self.size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: self.format,
-            view_formats: &[],
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
-            label: None,
-        };
+    /// * `encoder`: The command encoder into which the pass is recorded.
+    /// * `input_view`: The view which will be transformed into grayscale.
+    /// * `_inputs`: The additional inputs, which are not used by the grayscale pass.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        _inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let bind_group = self.bind_groups.get_or_insert_with(input_view, || {
+            create_bind_group(
+                device,
+                &self.bind_group_layout,
+                input_view,
+                &self.settings_buffer,
+            )
+        });
 
-        self.target = device
-            .create_texture(&wgpu::TextureDescriptor {
-                format: self.format,
-                ..texture_desc
-            })
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        render(encoder, &self.pipeline, bind_group, output_view);
+    }
+}
 
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: None,
-            entries: &[wgpu::BindGroupLayoutEntry {
+/// Creates the bind group which binds `input_view` as the input texture of the grayscale pass.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the bind group of the grayscale pipeline.
+/// * `input_view`: The view which will be transformed into grayscale.
+/// * `settings_buffer`: The uniform buffer containing the [GrayscaleSettings].
+///
+/// Returns:
+///
+/// The new bind group.
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    input_view: &wgpu::TextureView,
+    settings_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupEntry {
                 binding: 0,
-                visibility: wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Texture {
-                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    view_dimension: wgpu::TextureViewDimension::D2,
-                    multisampled: false,
-                },
-                count: None,
-            }],
-        });
+                resource: wgpu::BindingResource::TextureView(input_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: settings_buffer.as_entire_binding(),
+            },
+        ],
+        layout,
+    })
+}
 
-        self.bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: None,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: wgpu::BindingResource::TextureView(&self.target),
-            }],
-            layout: &bind_group_layout,
-        });
+/// Records the grayscale pass using an already created `bind_group`.
+///
+/// Arguments:
+///
+/// * `encoder`: The command encoder into which the pass is recorded.
+/// * `pipeline`: The grayscale pipeline.
+/// * `bind_group`: The bind group which contains the input texture.
+/// * `output_view`: The view into which the result is written.
+fn render(
+    encoder: &mut wgpu::CommandEncoder,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+    output_view: &wgpu::TextureView,
+) {
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: output_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: true,
+            },
+        })],
+        depth_stencil_attachment: None,
+        label: None,
+    });
+    rpass.set_pipeline(pipeline);
+    rpass.set_bind_group(0, bind_group, &[]);
+    rpass.draw(0..3, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grayscale::GrayscaleEffect, shader::validation, Effect};
+
+    #[test]
+    fn resize_keeps_pipeline_and_layout() {
+        let Some((device, _queue)) = crate::test_device::device() else {
+            return;
+        };
+
+        let size = wgpu::Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        };
+        let mut effect = GrayscaleEffect::new(&device, &size, wgpu::TextureFormat::Rgba8Unorm);
+
+        // The target of the frames is only allocated once a frame is started.
+        assert!(effect.state.target().is_none());
+        effect.state.prepare_target(&device);
+
+        let inner = effect.state.inner.as_ref().unwrap();
+        let pipeline = inner.pipeline.global_id();
+        let bind_group_layout = inner.bind_group_layout.global_id();
+        let target = effect.state.target().unwrap().view().global_id();
+
+        Effect::resize(&mut effect, &device, &wgpu::Extent3d { width: 8, ..size });
+
+        let inner = effect.state.inner.as_ref().unwrap();
+        assert_eq!(inner.pipeline.global_id(), pipeline);
+        assert_eq!(inner.bind_group_layout.global_id(), bind_group_layout);
+        assert_ne!(effect.state.target().unwrap().view().global_id(), target);
+    }
+
+    #[test]
+    fn shader_matches_bind_group_layout() {
+        validation::check_entry_point(
+            "grayscale/shader/grayscale.wgsl",
+            include_str!("shader/grayscale.wgsl"),
+            naga::ShaderStage::Fragment,
+            "fs_main",
+            &[&BIND_GROUP_LAYOUT_ENTRIES],
+        );
     }
 }
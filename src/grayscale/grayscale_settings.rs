@@ -0,0 +1,65 @@
+/// The weights which are used to compute the luma of a color.
+///
+/// See: https://en.wikipedia.org/wiki/Luma_(video).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LumaCoefficients {
+    /// The weights of ITU-R BT.601 used by standard definition video.
+    #[default]
+    Rec601,
+    /// The weights of ITU-R BT.709 used by HD video and the sRGB color space.
+    Rec709,
+    /// The weights of ITU-R BT.2020 used by UHD and HDR video.
+    Rec2020,
+    /// Custom weights of the red, green and blue channel, which should sum up to `1`.
+    Custom([f32; 3]),
+}
+
+impl LumaCoefficients {
+    /// Returns the weights of the red, green and blue channel.
+    pub fn weights(self) -> [f32; 3] {
+        match self {
+            LumaCoefficients::Rec601 => [0.299, 0.587, 0.114],
+            LumaCoefficients::Rec709 => [0.2126, 0.7152, 0.0722],
+            LumaCoefficients::Rec2020 => [0.2627, 0.678, 0.0593],
+            LumaCoefficients::Custom(weights) => weights,
+        }
+    }
+}
+
+/// The settings of the [crate::grayscale::GrayscaleEffect].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GrayscaleSettings {
+    /// The amount of desaturation in range `0..=1`.
+    ///
+    /// The original color is blended with the grayscale color, so that
+    /// `0` leaves the frame unchanged and `1` removes all colors.
+    pub strength: f32,
+
+    /// The weights which are used to compute the gray value of a color.
+    pub luma: LumaCoefficients,
+}
+
+impl Default for GrayscaleSettings {
+    fn default() -> Self {
+        Self {
+            strength: 1.0,
+            luma: LumaCoefficients::default(),
+        }
+    }
+}
+
+impl GrayscaleSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 16;
+
+    /// Converts the settings into the layout of the `GrayscaleSettings` struct in `grayscale.wgsl`.
+    pub(super) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        for (i, weight) in self.luma.weights().iter().enumerate() {
+            bytes[i * 4..i * 4 + 4].copy_from_slice(&weight.to_le_bytes());
+        }
+        bytes[12..16].copy_from_slice(&self.strength.to_le_bytes());
+
+        bytes
+    }
+}
@@ -1,5 +1,5 @@
 mod grayscale_effect;
-pub use grayscale_effect::GrayscaleEffect;
+pub use grayscale_effect::{GrayscaleEffect, GrayscaleFrame};
 mod grayscale_effect_inner;
-mod grayscale_frame;
-pub use grayscale_frame::GrayscaleFrame;
+mod grayscale_settings;
+pub use grayscale_settings::{GrayscaleSettings, LumaCoefficients};
@@ -1,28 +1,17 @@
-@group(0) @binding(0) var input_texture : texture_2d<f32>;
-
-struct VertexInput {
-    @builtin(vertex_index) vertex_index: u32,
+struct GrayscaleSettings {
+    luma_weights: vec3<f32>,
+    strength: f32,
 };
 
-@vertex
-fn vs_main(in: VertexInput) -> @builtin(position) vec4<f32> {
-    // Generate a triangle to fill the screen.
-    // The approach is based on: https://stackoverflow.com/a/59739538/4593433.
-    var fullscreen_vertecies = array(
-        vec4<f32>(-1.0, -1.0, 0.0, 1.0),
-        vec4<f32>(3.0, -1.0, 0.0, 1.0),
-        vec4<f32>(-1.0, 3.0, 0.0, 1.0)
-    );
-
-    return fullscreen_vertecies[in.vertex_index];
-}
+@group(0) @binding(0) var input_texture : texture_2d<f32>;
+@group(0) @binding(1) var<uniform> settings : GrayscaleSettings;
 
 @fragment
 fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
     let color = textureLoad(input_texture, vec2<i32>(pos.xy), 0).rgb;
 
     // Convert the color to luma: https://en.wikipedia.org/wiki/Luma_(video).
-    let gray = dot(color, vec3<f32>(0.299, 0.587, 0.114));
+    let gray = dot(color, settings.luma_weights);
 
-    return vec4<f32>(gray, gray, gray, 1.0);
+    return vec4<f32>(mix(color, vec3<f32>(gray), settings.strength), 1.0);
 }
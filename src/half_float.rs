@@ -0,0 +1,83 @@
+//! Conversions between single and half precision floats,
+//! which are used to upload and read back `*16Float` textures.
+
+/// Converts `value` into the bits of a half precision float, rounding to the nearest value.
+pub(crate) fn from_f32(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    // Infinity and NaN.
+    if exponent == 0xff {
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    // Values which can only be represented as subnormal numbers.
+    if exponent <= 0 {
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let round = (mantissa >> (shift - 1)) & 1;
+        return sign | ((mantissa >> shift) + round) as u16;
+    }
+
+    // A carry of the rounding correctly overflows into the exponent.
+    let round = (mantissa >> 12) & 1;
+    (sign | ((exponent as u16) << 10) | (mantissa >> 13) as u16) + round as u16
+}
+
+/// Converts the bits of a half precision float into a single precision float.
+pub(crate) fn to_f32(bits: u16) -> f32 {
+    let sign = u32::from(bits & 0x8000) << 16;
+    let exponent = u32::from((bits >> 10) & 0x1f);
+    let mantissa = u32::from(bits & 0x3ff);
+
+    let bits = match exponent {
+        // Zero and subnormal numbers, which are normalized in single precision.
+        0 => {
+            if mantissa == 0 {
+                sign
+            } else {
+                let shift = mantissa.leading_zeros() - 21;
+                let mantissa = (mantissa << shift) & 0x3ff;
+                sign | ((113 - shift) << 23) | (mantissa << 13)
+            }
+        }
+        // Infinity and NaN.
+        0x1f => sign | 0x7f80_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_round_trip() {
+        for value in [
+            0.0,
+            -0.0,
+            0.5,
+            1.0,
+            -2.0,
+            65504.0,
+            6.1035156e-5,
+            5.9604645e-8,
+        ] {
+            assert_eq!(to_f32(from_f32(value)), value);
+        }
+        assert_eq!(to_f32(from_f32(1e6)), f32::INFINITY);
+        assert!(to_f32(from_f32(f32::NAN)).is_nan());
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{effect_frame::FrameEffect, half_float, Effect, EffectFrame, Frame};
+
+/// An [Effect] which can be applied to images in memory.
+///
+/// This is implemented by every effect of this crate and the [crate::EffectChain],
+/// whose frames are [EffectFrame]s.
+pub trait HeadlessEffect:
+    Sized + FrameEffect + for<'a> Effect<Frame<'a> = EffectFrame<'a, Self>> + 'static
+{
+}
+
+impl<E> HeadlessEffect for E where
+    E: FrameEffect + for<'a> Effect<Frame<'a> = EffectFrame<'a, E>> + 'static
+{
+}
+
+/// Applies `effect` to an RGBA8 image without a window and reads the result back.
+///
+/// The effect must have been created using [wgpu::TextureFormat::Rgba8Unorm] as output format.
+/// The image is drawn into a frame started using [Effect::start_frame], which is then resolved
+/// like a rendered scene. This works with every adapter, including software adapters such as
+/// llvmpipe or lavapipe, so that effects can be used in tools and tested in CI.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `queue`: The queue which is used to upload the image and to run the effect.
+/// * `effect`: The effect or [crate::EffectChain] which is applied.
+/// * `size`: The size of the image, which must match the size of the effect.
+/// * `pixels`: The tightly packed RGBA8 pixels of the image, starting with the top row.
+///
+/// Returns:
+///
+/// The tightly packed RGBA8 pixels of the result or an error if the readback failed.
+pub fn process_rgba8<E: HeadlessEffect>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    effect: &mut E,
+    size: &wgpu::Extent3d,
+    pixels: &[u8],
+) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+    process(
+        device,
+        queue,
+        effect,
+        size,
+        wgpu::TextureFormat::Rgba8Unorm,
+        pixels,
+        wgpu::TextureFormat::Rgba8Unorm,
+    )
+}
+
+/// Applies `effect` to an RGBA16F image without a window and reads the result back.
+///
+/// The effect must have been created using [wgpu::TextureFormat::Rgba16Float] as output format.
+/// The values are converted to half precision before the effect is applied.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `queue`: The queue which is used to upload the image and to run the effect.
+/// * `effect`: The effect or [crate::EffectChain] which is applied.
+/// * `size`: The size of the image, which must match the size of the effect.
+/// * `pixels`: The tightly packed RGBA values of the image, starting with the top row.
+///
+/// Returns:
+///
+/// The tightly packed RGBA values of the result or an error if the readback failed.
+pub fn process_rgba16f<E: HeadlessEffect>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    effect: &mut E,
+    size: &wgpu::Extent3d,
+    pixels: &[f32],
+) -> Result<Vec<f32>, wgpu::BufferAsyncError> {
+    let result = process(
+        device,
+        queue,
+        effect,
+        size,
+        wgpu::TextureFormat::Rgba16Float,
+        &to_half_floats(pixels),
+        wgpu::TextureFormat::Rgba16Float,
+    )?;
+
+    Ok(from_half_floats(&result))
+}
+
+/// Applies `effect` to an RGBA16F image without a window and reads back an RGBA8 result,
+/// e.g. to test the tonemapping of HDR images.
+///
+/// The effect must have been created using [wgpu::TextureFormat::Rgba8Unorm] as output format.
+/// The values are converted to half precision before the effect is applied.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `queue`: The queue which is used to upload the image and to run the effect.
+/// * `effect`: The effect or [crate::EffectChain] which is applied.
+/// * `size`: The size of the image, which must match the size of the effect.
+/// * `pixels`: The tightly packed RGBA values of the image, starting with the top row.
+///
+/// Returns:
+///
+/// The tightly packed RGBA8 pixels of the result or an error if the readback failed.
+pub fn process_rgba16f_to_rgba8<E: HeadlessEffect>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    effect: &mut E,
+    size: &wgpu::Extent3d,
+    pixels: &[f32],
+) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+    process(
+        device,
+        queue,
+        effect,
+        size,
+        wgpu::TextureFormat::Rgba16Float,
+        &to_half_floats(pixels),
+        wgpu::TextureFormat::Rgba8Unorm,
+    )
+}
+
+/// Copies the first mip level of `texture` to the CPU.
+///
+/// The rows of the copy are padded to [wgpu::COPY_BYTES_PER_ROW_ALIGNMENT],
+/// this padding is removed from the result.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `queue`: The queue which is used to copy the texture.
+/// * `texture`: The texture which is read, it must have been created using
+///   [wgpu::TextureUsages::COPY_SRC] and an uncompressed color format.
+///
+/// Returns:
+///
+/// The tightly packed texels of the texture, starting with the top row,
+/// or an error if the buffer could not be mapped.
+pub fn read_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+    let size = texture.size();
+    let bytes_per_row = size.width * u32::from(texture.format().describe().block_size);
+    let padded_bytes_per_row = padded_bytes_per_row(bytes_per_row);
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: u64::from(padded_bytes_per_row * size.height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        wgpu::Extent3d {
+            depth_or_array_layers: 1,
+            ..size
+        },
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    receiver.recv().map_err(|_| wgpu::BufferAsyncError)??;
+
+    let pixels = slice
+        .get_mapped_range()
+        .chunks_exact(padded_bytes_per_row as usize)
+        .flat_map(|row| &row[..bytes_per_row as usize])
+        .copied()
+        .collect();
+    buffer.unmap();
+
+    Ok(pixels)
+}
+
+/// Uploads `pixels`, renders them into a frame of `effect` and reads the resolved result back.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `queue`: The queue which is used to upload the image and to run the effect.
+/// * `effect`: The effect which is applied.
+/// * `size`: The size of the image.
+/// * `input_format`: The format of the texture which contains the image.
+/// * `pixels`: The tightly packed texels of the image in `input_format`.
+/// * `output_format`: The format of the texture into which the frame is resolved.
+///
+/// Returns:
+///
+/// The tightly packed texels of the result in `output_format` or an error if the readback failed.
+fn process<E: HeadlessEffect>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    effect: &mut E,
+    size: &wgpu::Extent3d,
+    input_format: wgpu::TextureFormat,
+    pixels: &[u8],
+    output_format: wgpu::TextureFormat,
+) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+    let bytes_per_row = size.width * u32::from(input_format.describe().block_size);
+    assert_eq!(
+        pixels.len(),
+        (bytes_per_row * size.height) as usize,
+        "The number of pixels does not match the size of the image"
+    );
+
+    let input = device.create_texture(&wgpu::TextureDescriptor {
+        size: *size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: input_format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        label: None,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        input.as_image_copy(),
+        pixels,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(bytes_per_row),
+            rows_per_image: None,
+        },
+        *size,
+    );
+
+    let output = device.create_texture(&wgpu::TextureDescriptor {
+        size: *size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: output_format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        label: None,
+        view_formats: &[],
+    });
+    let input_view = input.create_view(&wgpu::TextureViewDescriptor::default());
+    let output_view = output.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    let frame = effect.start_frame(device, queue, &output_view);
+
+    // The image takes the place of the scene, so that it is drawn into the frame
+    // which is the output view itself if the effect is disabled or suspended.
+    let (format, sample_count) = match frame.effect.frame_target() {
+        Some(target) => (target.format(), target.sample_count()),
+        None => (output_format, 1),
+    };
+    // The blit is kept by the effect, so that it is only created once per format and sample count.
+    let mut blit = std::mem::take(frame.effect.image_blit());
+    blit.get(device, format, sample_count).draw(
+        device,
+        &mut encoder,
+        &input_view,
+        frame.color_attachment(wgpu::Operations {
+            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            store: true,
+        }),
+    );
+    *frame.effect.image_blit() = blit;
+    frame.resolve_with_encoder(&mut encoder);
+    queue.submit(Some(encoder.finish()));
+
+    read_texture(device, queue, &output)
+}
+
+/// Converts `values` into the little endian bytes of half precision floats.
+fn to_half_floats(values: &[f32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| half_float::from_f32(*value).to_le_bytes())
+        .collect()
+}
+
+/// Converts the little endian bytes of half precision floats into `f32` values.
+fn from_half_floats(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(2)
+        .map(|bits| half_float::to_f32(u16::from_le_bytes([bits[0], bits[1]])))
+        .collect()
+}
+
+/// Rounds `bytes_per_row` up to a multiple of [wgpu::COPY_BYTES_PER_ROW_ALIGNMENT].
+fn padded_bytes_per_row(bytes_per_row: u32) -> u32 {
+    wgpu::util::align_to(bytes_per_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_are_padded_to_the_copy_alignment() {
+        assert_eq!(padded_bytes_per_row(4), 256);
+        assert_eq!(padded_bytes_per_row(256), 256);
+        assert_eq!(padded_bytes_per_row(257), 512);
+        assert_eq!(padded_bytes_per_row(100 * 8), 1024);
+    }
+}
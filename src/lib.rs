@@ -5,6 +5,27 @@
 //! ## Effects
 //!
 //! * A simple [grayscale] effect.
+//! * Physically based [bloom].
+//! * A Gaussian or dual Kawase [blur].
+//! * Depth of field ([dof]).
+//! * Fast approximate anti-aliasing ([fxaa]).
+//! * Color grading using 3D lookup tables ([lut]).
+//! * Subpixel morphological anti-aliasing ([smaa]).
+//! * Temporal anti-aliasing ([taa]).
+//! * Tonemapping of HDR frames ([tonemap]).
+//!
+//! Multiple effects can be combined using an [EffectChain]. Every effect also implements
+//! the object safe [DynEffect] trait, so that effects can be selected at runtime.
+//!
+//! By default a frame is rendered using the output format. An [EffectFormat] can be passed
+//! instead of a plain [wgpu::TextureFormat] to render into e.g. an `Rgba16Float` frame,
+//! while the result is still written into an `*Srgb` swapchain.
+//!
+//! The constructors panic if a size or format is not supported. The fallible `try_new` and
+//! `try_resize` methods of every effect return an [Error] instead.
+//!
+//! The [headless] module applies effects to images in memory, which is useful for tools
+//! and for testing without a window or a dedicated GPU.
 //!
 //! ## Usage
 //! For example the _grayscale_ effect can be used in principle like so:
@@ -96,12 +117,89 @@
 
 #![deny(missing_docs)]
 
+mod bind_group_cache;
+
+mod blit;
+
+mod chain;
+pub use chain::{EffectChain, EffectChainFrame};
+
+mod dyn_effect;
+pub use dyn_effect::DynEffect;
+
 mod effect;
 pub use effect::Effect;
 
+mod effect_format;
+pub use effect_format::EffectFormat;
+
+mod effect_frame;
+pub use effect_frame::EffectFrame;
+
+#[cfg(any(
+    feature = "bloom",
+    feature = "blur",
+    feature = "dof",
+    feature = "fxaa",
+    feature = "grayscale",
+    feature = "lut",
+    feature = "smaa",
+    feature = "taa",
+    feature = "tonemap"
+))]
+mod effect_state;
+
+mod error;
+pub use error::Error;
+
 mod frame;
 pub use frame::Frame;
 
+mod half_float;
+
+/// Runs effects on images without a window and reads the results back to the CPU.
+pub mod headless;
+
+mod render_target;
+
+mod shader;
+
+/// Requests the graphics device of the unit tests which need an adapter.
+#[cfg(test)]
+mod test_device;
+
+/// Adds a glow around bright areas using a physically based bloom.
+#[cfg(feature = "bloom")]
+pub mod bloom;
+
+/// Blurs frames or arbitrary textures using a Gaussian or dual Kawase blur.
+#[cfg(feature = "blur")]
+pub mod blur;
+
+/// Blurs out of focus areas of frames using the depth of the scene.
+#[cfg(feature = "dof")]
+pub mod dof;
+
+/// Smooths jagged edges using fast approximate anti-aliasing.
+#[cfg(feature = "fxaa")]
+pub mod fxaa;
+
 /// Transforms colorized frames into a simple grayscale version.
 #[cfg(feature = "grayscale")]
 pub mod grayscale;
+
+/// Grades the colors of frames using 3D lookup tables.
+#[cfg(feature = "lut")]
+pub mod lut;
+
+/// Smooths jagged edges using subpixel morphological anti-aliasing.
+#[cfg(feature = "smaa")]
+pub mod smaa;
+
+/// Reduces shimmering and aliasing using temporal anti-aliasing.
+#[cfg(feature = "taa")]
+pub mod taa;
+
+/// Maps HDR frames into the displayable range using tonemapping operators.
+#[cfg(feature = "tonemap")]
+pub mod tonemap;
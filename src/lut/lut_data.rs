@@ -0,0 +1,397 @@
+use crate::half_float;
+
+use super::LutError;
+
+/// The range of sizes supported by [Lut].
+const SIZE_RANGE: std::ops::RangeInclusive<u32> = 2..=256;
+
+/// A 3D color lookup table which is applied by the [crate::lut::LutEffect].
+///
+/// The entries are ordered such that red changes fastest, followed by green and blue,
+/// which matches the order of the `.cube` format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lut {
+    size: u32,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    data: Vec<[f32; 3]>,
+}
+
+impl Lut {
+    /// The size of the description of the LUT when uploaded to a uniform buffer.
+    pub(super) const INFO_SIZE: usize = 48;
+
+    /// Creates a [Lut] which maps every color onto itself.
+    ///
+    /// Arguments:
+    ///
+    /// * `size`: The number of entries along each axis in range `2..=256`.
+    ///
+    /// Returns:
+    ///
+    /// The new [Lut] or [LutError::InvalidSize] if the size is not supported.
+    pub fn identity(size: u32) -> Result<Lut, LutError> {
+        check_size(size)?;
+
+        let max = (size - 1) as f32;
+        let data = (0..size * size * size)
+            .map(|i| {
+                [
+                    (i % size) as f32 / max,
+                    (i / size % size) as f32 / max,
+                    (i / (size * size)) as f32 / max,
+                ]
+            })
+            .collect();
+
+        Ok(Lut {
+            size,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            data,
+        })
+    }
+
+    /// Parses a [Lut] from the contents of an Adobe / Resolve `.cube` file.
+    ///
+    /// Comments, `TITLE`, `DOMAIN_MIN`, `DOMAIN_MAX` and `LUT_3D_INPUT_RANGE` are
+    /// supported, other keywords are ignored. 1D LUTs cannot be used, and the domain
+    /// must not be empty on any channel.
+    ///
+    /// Arguments:
+    ///
+    /// * `source`: The text of the `.cube` file.
+    ///
+    /// Returns:
+    ///
+    /// The parsed [Lut] or a [LutError] describing why the file is invalid.
+    pub fn from_cube(source: &str) -> Result<Lut, LutError> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut data = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let invalid = LutError::InvalidLine { line: index + 1 };
+
+            let line = line.split('#').next().unwrap_or_default();
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+
+            if keyword.parse::<f32>().is_ok() {
+                data.push(parse_floats(line.split_whitespace()).ok_or(invalid)?);
+                continue;
+            }
+
+            match keyword {
+                "LUT_3D_SIZE" => {
+                    let value = tokens.next().and_then(|t| t.parse().ok()).ok_or(invalid)?;
+                    size = Some(check_size(value)?);
+                }
+                "LUT_1D_SIZE" => return Err(LutError::Unsupported1d),
+                "DOMAIN_MIN" => domain_min = parse_floats(tokens).ok_or(invalid)?,
+                "DOMAIN_MAX" => domain_max = parse_floats(tokens).ok_or(invalid)?,
+                "LUT_3D_INPUT_RANGE" => {
+                    let range: Vec<f32> = tokens.filter_map(|t| t.parse().ok()).collect();
+                    let [min, max] = range[..] else {
+                        return Err(invalid);
+                    };
+                    domain_min = [min; 3];
+                    domain_max = [max; 3];
+                }
+                // `TITLE` and vendor specific keywords do not affect the LUT.
+                _ if keyword.starts_with(|c: char| c.is_ascii_uppercase()) => {}
+                _ => return Err(invalid),
+            }
+        }
+
+        let size = size.ok_or(LutError::MissingSize)?;
+        // The shader divides by the extent of the domain.
+        if domain_min
+            .iter()
+            .zip(domain_max)
+            .any(|(min, max)| *min == max)
+        {
+            return Err(LutError::EmptyDomain);
+        }
+
+        let expected = (size * size * size) as usize;
+        if data.len() != expected {
+            return Err(LutError::InvalidLength {
+                expected,
+                found: data.len(),
+            });
+        }
+
+        Ok(Lut {
+            size,
+            domain_min,
+            domain_max,
+            data,
+        })
+    }
+
+    /// Creates a [Lut] from a 2D strip image, as used e.g. by Unity.
+    ///
+    /// The strip consists of `size` square slices of `size * size` pixels placed
+    /// side by side, so that the image is `size * size` pixels wide and `size` pixels high.
+    /// Red increases from left to right within each slice, blue increases from
+    /// slice to slice and green increases from the top row to the bottom row.
+    /// Images where green increases upwards must be flipped vertically beforehand.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: The pixels of the strip as tightly packed RGBA8 values, starting with the top row.
+    /// * `size`: The number of entries along each axis in range `2..=256`.
+    ///
+    /// Returns:
+    ///
+    /// The new [Lut] or a [LutError] if the image does not match the size.
+    pub fn from_strip(bytes: &[u8], size: u32) -> Result<Lut, LutError> {
+        check_size(size)?;
+
+        let expected = (size * size * size * 4) as usize;
+        if bytes.len() != expected {
+            return Err(LutError::InvalidLength {
+                expected,
+                found: bytes.len(),
+            });
+        }
+
+        let width = size * size;
+        let data = (0..size * size * size)
+            .map(|i| {
+                let (r, g, b) = (i % size, i / size % size, i / (size * size));
+                let offset = ((g * width + b * size + r) * 4) as usize;
+                let pixel = &bytes[offset..offset + 3];
+                [pixel[0], pixel[1], pixel[2]].map(|c| f32::from(c) / 255.0)
+            })
+            .collect();
+
+        Ok(Lut {
+            size,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            data,
+        })
+    }
+
+    /// Returns the number of entries along each axis.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the input color which is mapped by the first entry.
+    pub fn domain_min(&self) -> [f32; 3] {
+        self.domain_min
+    }
+
+    /// Returns the input color which is mapped by the last entry.
+    pub fn domain_max(&self) -> [f32; 3] {
+        self.domain_max
+    }
+
+    /// Returns the entries, where red changes fastest, followed by green and blue.
+    pub fn data(&self) -> &[[f32; 3]] {
+        &self.data
+    }
+
+    /// Converts the size and domain into the layout of the `LutInfo` struct in `lut.wgsl`.
+    ///
+    /// Arguments:
+    ///
+    /// * `encode_input`: Whether the input stores linear colors, which must be encoded before the lookup.
+    /// * `decode_output`: Whether the output expects linear colors, which must be decoded after the lookup.
+    pub(super) fn info_bytes(
+        &self,
+        encode_input: bool,
+        decode_output: bool,
+    ) -> [u8; Self::INFO_SIZE] {
+        let mut bytes = [0; Self::INFO_SIZE];
+        for (i, value) in self.domain_min.iter().enumerate() {
+            bytes[i * 4..i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        bytes[12..16].copy_from_slice(&(self.size as f32).to_le_bytes());
+        for (i, value) in self.domain_max.iter().enumerate() {
+            bytes[16 + i * 4..20 + i * 4].copy_from_slice(&value.to_le_bytes());
+        }
+        bytes[28..32].copy_from_slice(&u32::from(encode_input).to_le_bytes());
+        bytes[32..36].copy_from_slice(&u32::from(decode_output).to_le_bytes());
+
+        bytes
+    }
+
+    /// Converts the entries into the layout of an `Rgba16Float` texture.
+    pub(super) fn to_rgba16_bytes(&self) -> Vec<u8> {
+        self.data
+            .iter()
+            .flat_map(|[r, g, b]| [*r, *g, *b, 1.0])
+            .flat_map(|c| half_float::from_f32(c).to_le_bytes())
+            .collect()
+    }
+}
+
+/// Validates that `size` is in the range supported by [Lut].
+fn check_size(size: u32) -> Result<u32, LutError> {
+    if SIZE_RANGE.contains(&size) {
+        Ok(size)
+    } else {
+        Err(LutError::InvalidSize(size))
+    }
+}
+
+/// Parses exactly three floats from `tokens`.
+fn parse_floats<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Option<[f32; 3]> {
+    let mut values = [0.0; 3];
+    for value in values.iter_mut() {
+        *value = tokens.next()?.parse().ok()?;
+    }
+
+    tokens.next().is_none().then_some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the lines of an identity `.cube` file with the given size.
+    fn identity_lines(size: u32) -> Vec<String> {
+        let lut = Lut::identity(size).unwrap();
+        lut.data()
+            .iter()
+            .map(|[r, g, b]| format!("{r} {g} {b}"))
+            .collect()
+    }
+
+    #[test]
+    fn cube_ignores_comments_blank_lines_and_title() {
+        let source = format!(
+            "# Created by hand\n\nTITLE \"Identity\"\nLUT_3D_SIZE 2 # trailing comment\n\n{}\n",
+            identity_lines(2).join("\n# between entries\n")
+        );
+
+        assert_eq!(Lut::from_cube(&source), Lut::identity(2));
+    }
+
+    #[test]
+    fn cube_reads_the_domain() {
+        let source = format!(
+            "LUT_3D_SIZE 2\nDOMAIN_MIN 0 -1 0.5\nDOMAIN_MAX 1 2 4\n{}",
+            identity_lines(2).join("\n")
+        );
+        let lut = Lut::from_cube(&source).unwrap();
+
+        assert_eq!(lut.domain_min(), [0.0, -1.0, 0.5]);
+        assert_eq!(lut.domain_max(), [1.0, 2.0, 4.0]);
+    }
+
+    #[test]
+    fn cube_reads_the_input_range() {
+        let source = format!(
+            "LUT_3D_INPUT_RANGE 0.0 4.0\nLUT_3D_SIZE 2\n{}",
+            identity_lines(2).join("\n")
+        );
+        let lut = Lut::from_cube(&source).unwrap();
+
+        assert_eq!(lut.domain_min(), [0.0; 3]);
+        assert_eq!(lut.domain_max(), [4.0; 3]);
+
+        let source = format!(
+            "LUT_3D_INPUT_RANGE 0.0\nLUT_3D_SIZE 2\n{}",
+            identity_lines(2).join("\n")
+        );
+        assert_eq!(
+            Lut::from_cube(&source),
+            Err(LutError::InvalidLine { line: 1 })
+        );
+    }
+
+    #[test]
+    fn cube_rejects_an_empty_domain() {
+        for domain in [
+            "DOMAIN_MIN 0 0.5 0\nDOMAIN_MAX 1 0.5 1",
+            "LUT_3D_INPUT_RANGE 1 1",
+        ] {
+            let source = format!("LUT_3D_SIZE 2\n{domain}\n{}", identity_lines(2).join("\n"));
+
+            assert_eq!(Lut::from_cube(&source), Err(LutError::EmptyDomain));
+        }
+    }
+
+    #[test]
+    fn cube_rejects_a_wrong_number_of_entries() {
+        let mut lines = identity_lines(2);
+        lines.pop();
+        let source = format!("LUT_3D_SIZE 2\n{}", lines.join("\n"));
+
+        assert_eq!(
+            Lut::from_cube(&source),
+            Err(LutError::InvalidLength {
+                expected: 8,
+                found: 7
+            })
+        );
+        assert_eq!(Lut::from_cube("0 0 0"), Err(LutError::MissingSize));
+        assert_eq!(
+            Lut::from_cube("LUT_3D_SIZE 1"),
+            Err(LutError::InvalidSize(1))
+        );
+    }
+
+    #[test]
+    fn cube_rejects_1d_luts() {
+        let source = "LUT_1D_SIZE 2\n0 0 0\n1 1 1";
+
+        assert_eq!(Lut::from_cube(source), Err(LutError::Unsupported1d));
+    }
+
+    #[test]
+    fn cube_rejects_malformed_floats() {
+        for entry in ["0 0", "0 0 0 0", "0 0 x", "0 0.5.5 0", "zero 0 0"] {
+            let source = format!(
+                "LUT_3D_SIZE 2\n{}\n{entry}",
+                identity_lines(2)[1..].join("\n")
+            );
+
+            assert_eq!(
+                Lut::from_cube(&source),
+                Err(LutError::InvalidLine { line: 9 }),
+                "{entry}"
+            );
+        }
+        assert_eq!(
+            Lut::from_cube("LUT_3D_SIZE two"),
+            Err(LutError::InvalidLine { line: 1 })
+        );
+        assert_eq!(
+            Lut::from_cube("DOMAIN_MIN 0 0"),
+            Err(LutError::InvalidLine { line: 1 })
+        );
+    }
+
+    #[test]
+    fn strip_orders_red_green_blue() {
+        // Slices of 2x2 pixels side by side, where each pixel stores its own coordinates.
+        let size = 2;
+        let mut bytes = Vec::new();
+        for g in 0..size {
+            for b in 0..size {
+                for r in 0..size {
+                    bytes.extend([r * 255, g * 255, b * 255, 255]);
+                }
+            }
+        }
+        let lut = Lut::from_strip(&bytes, size as u32).unwrap();
+
+        assert_eq!(Some(lut), Lut::identity(2).ok());
+        assert_eq!(
+            Lut::from_strip(&bytes[4..], 2),
+            Err(LutError::InvalidLength {
+                expected: 32,
+                found: 28
+            })
+        );
+    }
+}
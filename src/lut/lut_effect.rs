@@ -0,0 +1,39 @@
+use crate::{
+    effect_state::{impl_effect, EffectState},
+    EffectFrame,
+};
+
+use super::{lut_effect_inner::LutEffectInner, Lut, LutSettings};
+
+/// Grades the colors of a frame using a 3D lookup table ([Lut]).
+///
+/// The LUT is applied to colors encoded using the sRGB transfer function, as
+/// expected by grading tools. Linear inputs (`*Srgb` and floating point formats)
+/// are encoded before the lookup and decoded again for linear outputs.
+pub struct LutEffect {
+    pub(super) state: EffectState<LutEffectInner>,
+    pub(super) settings: LutSettings,
+    lut: Lut,
+}
+
+/// The frame into which the scene is rendered before the [LutEffect] is resolved.
+pub type LutFrame<'a> = EffectFrame<'a, LutEffect>;
+
+impl LutEffect {
+    /// Creates the resources of the effect using the LUT and the current format and settings.
+    fn create_inner(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> LutEffectInner {
+        LutEffectInner::new(
+            device,
+            queue,
+            self.state.format(),
+            &self.lut,
+            &self.settings,
+        )
+    }
+}
+
+impl_effect!(
+    LutEffect, "color grading", LutSettings, queue;
+    /// The LUT which is applied to the frame.
+    lut: &Lut,
+);
@@ -0,0 +1,307 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_format::is_linear,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::{Lut, LutSettings};
+
+/// The format of the 3D texture which stores the LUT.
+const LUT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// The entries of the bind group layout, which match the bindings of `shader/lut.wgsl`.
+const BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 5] = [
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D3,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 2,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 3,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(LutSettings::SIZE as u64),
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 4,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(Lut::INFO_SIZE as u64),
+        },
+        count: None,
+    },
+];
+
+/// Wraps the internal fields and implementation
+/// of a [crate::lut::LutEffect].
+pub(super) struct LutEffectInner {
+    pub(super) pipeline: wgpu::RenderPipeline,
+    pub(super) _vertex_shader: VertexShader,
+    pub(super) bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) lut_view: wgpu::TextureView,
+    pub(super) sampler: wgpu::Sampler,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) info_buffer: wgpu::Buffer,
+    pub(super) bind_groups: BindGroupCache,
+}
+
+impl LutEffectInner {
+    /// Creates a new [LutEffectInner].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `queue`: The command queue used to upload the LUT.
+    /// * `format`: The texture format of the post-processed frame.
+    /// * `lut`: The LUT which is applied to the frame.
+    /// * `settings`: The settings of the color grading.
+    ///
+    /// Returns:
+    ///
+    /// The new [LutEffectInner].
+    pub(super) fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: EffectFormat,
+        lut: &Lut,
+        settings: &LutSettings,
+    ) -> Self {
+        let intermediate = format.intermediate_or(device, format.output);
+        let lut_size = wgpu::Extent3d {
+            width: lut.size(),
+            height: lut.size(),
+            depth_or_array_layers: lut.size(),
+        };
+        let lut_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: lut_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format: LUT_FORMAT,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            lut_texture.as_image_copy(),
+            &lut.to_rgba16_bytes(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(lut.size() * 8),
+                rows_per_image: std::num::NonZeroU32::new(lut.size()),
+            },
+            lut_size,
+        );
+        let lut_view = lut_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &settings.to_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let info_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &lut.info_bytes(is_linear(intermediate), is_linear(format.output)),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &BIND_GROUP_LAYOUT_ENTRIES,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let lut_shader = include_composed_wgsl!(device, "shader/lut.wgsl");
+
+        let lut_shader_vert = vertex_shader.state();
+
+        let lut_shader_frag = wgpu::FragmentState {
+            module: &lut_shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: format.output,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent::REPLACE,
+                    alpha: wgpu::BlendComponent::REPLACE,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        };
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: lut_shader_vert,
+            fragment: Some(lut_shader_frag),
+            primitive: Default::default(),
+            multisample: Default::default(),
+            depth_stencil: None,
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            _vertex_shader: vertex_shader,
+            bind_group_layout,
+            lut_view,
+            sampler,
+            settings_buffer,
+            info_buffer,
+            bind_groups: BindGroupCache::new(),
+        }
+    }
+
+    /// Uploads new settings into the uniform buffer.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device, which is not needed to upload the settings.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the color grading.
+    pub(super) fn set_settings(
+        &self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &LutSettings,
+    ) {
+        queue.write_buffer(&self.settings_buffer, 0, &settings.to_bytes());
+    }
+}
+
+impl EffectInner for LutEffectInner {
+    /// Releases the bind groups of the previous frame targets, because
+    /// the color grading pass itself does not depend on the size of the frame.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device.
+    /// * `_size`: The new size of the frame.
+    fn resize(&mut self, _device: &wgpu::Device, _size: &wgpu::Extent3d) {
+        self.bind_groups.clear();
+    }
+
+    /// Records the color grading pass which reads from `input_view`
+    /// and writes into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the pass is recorded.
+    /// * `input_view`: The view which will be graded.
+    /// * `_inputs`: The additional inputs, which are not used by the color grading pass.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        _inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let bind_group = self.bind_groups.get_or_insert_with(input_view, || {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(input_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&self.lut_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: self.settings_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: self.info_buffer.as_entire_binding(),
+                    },
+                ],
+                layout: &self.bind_group_layout,
+            })
+        });
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+            label: None,
+        });
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shader_matches_bind_group_layout() {
+        validation::check_entry_point(
+            "lut/shader/lut.wgsl",
+            include_str!("shader/lut.wgsl"),
+            naga::ShaderStage::Fragment,
+            "fs_main",
+            &[&BIND_GROUP_LAYOUT_ENTRIES],
+        );
+    }
+}
@@ -0,0 +1,49 @@
+/// The errors which can occur while loading a [crate::lut::Lut].
+#[derive(Clone, Debug, PartialEq)]
+pub enum LutError {
+    /// A line of a `.cube` file could neither be parsed as a keyword nor as data.
+    InvalidLine {
+        /// The number of the line, starting at 1.
+        line: usize,
+    },
+    /// The `.cube` file describes a 1D LUT, which is not supported.
+    Unsupported1d,
+    /// The `.cube` file does not contain the `LUT_3D_SIZE` keyword.
+    MissingSize,
+    /// `DOMAIN_MIN` and `DOMAIN_MAX` are equal on at least one channel, so that
+    /// no input color can be mapped onto the entries of the LUT.
+    EmptyDomain,
+    /// The size of the LUT is outside of the supported range `2..=256`.
+    InvalidSize(u32),
+    /// The number of entries does not match the size of the LUT.
+    InvalidLength {
+        /// The number of entries (or bytes for strip images) required by the size.
+        expected: usize,
+        /// The number of entries (or bytes for strip images) which were found.
+        found: usize,
+    },
+}
+
+impl std::fmt::Display for LutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LutError::InvalidLine { line } => write!(f, "invalid LUT data in line {line}"),
+            LutError::Unsupported1d => write!(f, "1D LUTs are not supported"),
+            LutError::MissingSize => write!(f, "the LUT_3D_SIZE keyword is missing"),
+            LutError::EmptyDomain => {
+                write!(
+                    f,
+                    "DOMAIN_MIN and DOMAIN_MAX are equal on at least one channel"
+                )
+            }
+            LutError::InvalidSize(size) => {
+                write!(f, "the LUT size {size} is outside of the range 2..=256")
+            }
+            LutError::InvalidLength { expected, found } => {
+                write!(f, "expected {expected} LUT entries, but found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LutError {}
@@ -0,0 +1,28 @@
+/// The settings of the [crate::lut::LutEffect].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LutSettings {
+    /// The amount of color grading in range `0..=1`.
+    ///
+    /// The original color is blended with the graded color, so that
+    /// `0` disables the effect and `1` applies the LUT completely.
+    pub strength: f32,
+}
+
+impl Default for LutSettings {
+    fn default() -> Self {
+        Self { strength: 1.0 }
+    }
+}
+
+impl LutSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 16;
+
+    /// Converts the settings into the layout of the `LutSettings` struct in `lut.wgsl`.
+    pub(super) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.strength.to_le_bytes());
+
+        bytes
+    }
+}
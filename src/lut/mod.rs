@@ -0,0 +1,9 @@
+mod lut_data;
+pub use lut_data::Lut;
+mod lut_effect;
+pub use lut_effect::{LutEffect, LutFrame};
+mod lut_effect_inner;
+mod lut_error;
+pub use lut_error::LutError;
+mod lut_settings;
+pub use lut_settings::LutSettings;
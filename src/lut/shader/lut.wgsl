@@ -0,0 +1,47 @@
+#include "color.wgsl"
+
+struct LutSettings {
+    strength: f32,
+};
+
+struct LutInfo {
+    domain_min: vec3<f32>,
+    size: f32,
+    domain_max: vec3<f32>,
+    encode_input: u32,
+    decode_output: u32,
+};
+
+@group(0) @binding(0) var input_texture : texture_2d<f32>;
+@group(0) @binding(1) var lut_texture : texture_3d<f32>;
+@group(0) @binding(2) var lut_sampler : sampler;
+@group(0) @binding(3) var<uniform> settings : LutSettings;
+@group(0) @binding(4) var<uniform> lut : LutInfo;
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let input = textureLoad(input_texture, vec2<i32>(pos.xy), 0);
+
+    // Grading tools create LUTs for display encoded colors.
+    var color = max(input.rgb, vec3<f32>(0.0));
+    if (lut.encode_input != 0u) {
+        color = encode_srgb(color);
+    }
+
+    // Map the domain onto the centers of the first and last texels,
+    // so that the hardware filtering interpolates trilinearly between the entries.
+    let coords = clamp(
+        (color - lut.domain_min) / (lut.domain_max - lut.domain_min),
+        vec3<f32>(0.0),
+        vec3<f32>(1.0)
+    );
+    let uvw = (coords * (lut.size - 1.0) + 0.5) / lut.size;
+    let graded = textureSampleLevel(lut_texture, lut_sampler, uvw, 0.0).rgb;
+
+    color = mix(color, graded, settings.strength);
+    if (lut.decode_output != 0u) {
+        color = decode_srgb(max(color, vec3<f32>(0.0)));
+    }
+
+    return vec4<f32>(color, input.a);
+}
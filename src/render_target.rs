@@ -0,0 +1,238 @@
+/// The size of the resources of a suspended effect, because textures cannot be empty.
+const SUSPENDED_SIZE: wgpu::Extent3d = wgpu::Extent3d {
+    width: 1,
+    height: 1,
+    depth_or_array_layers: 1,
+};
+
+/// Returns whether `size` contains no pixels, e.g. because the window is minimized.
+pub(crate) fn is_empty(size: &wgpu::Extent3d) -> bool {
+    size.width == 0 || size.height == 0
+}
+
+/// Returns the size of the resources of an effect of `size`,
+/// which is minimal while the effect is suspended.
+pub(crate) fn allocation_size(size: &wgpu::Extent3d) -> wgpu::Extent3d {
+    if is_empty(size) {
+        SUSPENDED_SIZE
+    } else {
+        *size
+    }
+}
+
+/// Creates a texture which is used as a render target and sampled afterwards.
+///
+/// This is shared by the intermediate textures of all effects, which are
+/// reallocated using this function whenever an effect is resized.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `size`: The size of the texture.
+/// * `format`: The format of the texture.
+///
+/// Returns:
+///
+/// The view of the new texture.
+pub(crate) fn create_texture_view(
+    device: &wgpu::Device,
+    size: &wgpu::Extent3d,
+    format: wgpu::TextureFormat,
+) -> wgpu::TextureView {
+    device
+        .create_texture(&texture_descriptor(size, format))
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// Returns the descriptor of a single sampled texture created by [create_texture_view].
+fn texture_descriptor(
+    size: &wgpu::Extent3d,
+    format: wgpu::TextureFormat,
+) -> wgpu::TextureDescriptor<'static> {
+    wgpu::TextureDescriptor {
+        size: *size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        label: None,
+        view_formats: &[],
+    }
+}
+
+/// The texture into which a frame is rendered before the post-processing is applied.
+///
+/// If the target is multisampled, an additional multisampled texture is allocated
+/// which is rendered into and resolved into the single sampled texture at the
+/// end of the render pass. The effect itself only ever reads the resolved texture.
+///
+/// Optionally the target also owns a depth texture with the same sample count,
+/// which can be used as the depth attachment while rendering into the frame.
+/// Effects only own depth textures for single sampled targets, so that they can sample it.
+pub struct RenderTarget {
+    view: wgpu::TextureView,
+    multisampled_view: Option<wgpu::TextureView>,
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    depth: Option<DepthTarget>,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
+/// The optional depth texture of a [RenderTarget].
+#[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+struct DepthTarget {
+    /// The view which is used as the depth attachment.
+    view: wgpu::TextureView,
+    /// The view of the depth aspect, which can be sampled by effects.
+    sampled_view: wgpu::TextureView,
+    format: wgpu::TextureFormat,
+}
+
+impl RenderTarget {
+    /// Creates a new [RenderTarget].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The size of the target.
+    /// * `format`: The texture format of the target.
+    /// * `sample_count`: The number of samples per pixel, `1` disables multisampling.
+    ///
+    /// Returns:
+    ///
+    /// The new [RenderTarget].
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let view = create_texture_view(device, size, format);
+
+        let multisampled_view = (sample_count > 1).then(|| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    sample_count,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    ..texture_descriptor(size, format)
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        });
+
+        Self {
+            view,
+            multisampled_view,
+            #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+            depth: None,
+            format,
+            sample_count,
+        }
+    }
+
+    /// Allocates or releases the depth texture of the [RenderTarget].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The size of the target.
+    /// * `depth_format`: The format of the depth texture, `None` releases the depth texture.
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    pub(crate) fn set_depth_format(
+        &mut self,
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+        depth_format: Option<wgpu::TextureFormat>,
+    ) {
+        self.depth = depth_format.map(|format| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                size: *size,
+                mip_level_count: 1,
+                sample_count: self.sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                label: None,
+                view_formats: &[],
+            });
+
+            DepthTarget {
+                view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                sampled_view: texture.create_view(&wgpu::TextureViewDescriptor {
+                    aspect: wgpu::TextureAspect::DepthOnly,
+                    ..Default::default()
+                }),
+                format,
+            }
+        });
+    }
+
+    /// Recreates the textures of the [RenderTarget] with a new `size`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size of the target.
+    #[cfg(any(
+        feature = "bloom",
+        feature = "blur",
+        feature = "dof",
+        feature = "fxaa",
+        feature = "grayscale",
+        feature = "lut",
+        feature = "smaa",
+        feature = "taa",
+        feature = "tonemap"
+    ))]
+    pub(crate) fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+        let depth_format = self.depth.as_ref().map(|depth| depth.format);
+
+        *self = RenderTarget::new(device, size, self.format, self.sample_count);
+        #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+        self.set_depth_format(device, size, depth_format);
+    }
+
+    /// Returns the single sampled view, which contains the resolved frame.
+    pub(crate) fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Returns the view which should be used as the color attachment while rendering.
+    ///
+    /// This is the multisampled view if multisampling is enabled.
+    pub(crate) fn attachment_view(&self) -> &wgpu::TextureView {
+        self.multisampled_view.as_ref().unwrap_or(&self.view)
+    }
+
+    /// Returns the view into which the multisampled view must be resolved,
+    /// or `None` if multisampling is disabled.
+    pub(crate) fn resolve_target(&self) -> Option<&wgpu::TextureView> {
+        self.multisampled_view.as_ref().map(|_| &self.view)
+    }
+
+    /// Returns the view of the depth texture which should be used as the
+    /// depth attachment while rendering, or `None` if there is no depth texture.
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    pub(crate) fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.depth.as_ref().map(|depth| &depth.view)
+    }
+
+    /// Returns the view of the depth aspect of the depth texture, which can be
+    /// bound by effects, or `None` if there is no depth texture.
+    #[cfg(any(feature = "dof", feature = "smaa", feature = "taa"))]
+    pub(crate) fn sampled_depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.depth.as_ref().map(|depth| &depth.sampled_view)
+    }
+
+    /// Returns the format of the views of the target.
+    pub(crate) fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// Returns the number of samples per pixel of the attachment view.
+    pub(crate) fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+}
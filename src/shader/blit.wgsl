@@ -0,0 +1,10 @@
+// Copies a texture into a render target of any format, which is used
+// to pass frames through disabled effects.
+#include "sampling.wgsl"
+
+@group(0) @binding(0) var input_texture : texture_2d<f32>;
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    return load_clamped(input_texture, vec2<i32>(pos.xy));
+}
@@ -0,0 +1,26 @@
+// Color space conversions which are shared by the effects.
+
+// Converts a linear color into the sRGB transfer function.
+fn encode_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+// Converts a color encoded using the sRGB transfer function back into linear.
+fn decode_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+// Converts a color to luma using the Rec. 601 coefficients: https://en.wikipedia.org/wiki/Luma_(video).
+fn luma_rec601(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.299, 0.587, 0.114));
+}
+
+// Converts a color to luma using the Rec. 709 coefficients, which equals the
+// relative luminance for linear colors: https://en.wikipedia.org/wiki/Relative_luminance.
+fn luma_rec709(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
@@ -0,0 +1,77 @@
+/// The WGSL files which can be included by the shaders of the effects.
+const LIBRARY: [(&str, &str); 4] = [
+    ("color.wgsl", include_str!("color.wgsl")),
+    ("fullscreen.wgsl", include_str!("fullscreen.wgsl")),
+    (
+        "fullscreen_vertex.wgsl",
+        include_str!("fullscreen_vertex.wgsl"),
+    ),
+    ("sampling.wgsl", include_str!("sampling.wgsl")),
+];
+
+/// Resolves the `#include "<file>"` directives of a WGSL `source`.
+///
+/// Every directive is replaced by the contents of the named file of the shared
+/// library in `src/shader`, which may include other files themselves.
+/// Each file is only included once, so that its declarations are not duplicated.
+///
+/// Arguments:
+///
+/// * `source`: The WGSL source code of a shader.
+///
+/// Returns:
+///
+/// The WGSL source code without any directives.
+///
+/// Panics if a directive names a file which is not part of the library.
+pub(crate) fn compose(source: &str) -> String {
+    let mut composed = String::with_capacity(source.len());
+    append(source, &mut composed, &mut Vec::new());
+    composed
+}
+
+/// Appends `source` to `composed`, replacing the directives by the files
+/// which are not part of `included` yet.
+fn append<'a>(source: &'a str, composed: &mut String, included: &mut Vec<&'a str>) {
+    for line in source.lines() {
+        let Some(file) = line.trim().strip_prefix("#include") else {
+            composed.push_str(line);
+            composed.push('\n');
+            continue;
+        };
+
+        let file = file.trim().trim_matches('"');
+        if included.contains(&file) {
+            continue;
+        }
+        let Some((_, library_source)) = LIBRARY.iter().find(|(name, _)| *name == file) else {
+            panic!("The WGSL file {file:?} cannot be included");
+        };
+
+        included.push(file);
+        append(library_source, composed, included);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn includes_are_resolved_once() {
+        let composed = compose(
+            "#include \"fullscreen_vertex.wgsl\"\n  #include \"fullscreen.wgsl\"\nfn main() {}",
+        );
+
+        assert!(!composed.contains("#include"));
+        assert_eq!(composed.matches("struct FullscreenVertex").count(), 1);
+        assert_eq!(composed.matches("fn vs_main").count(), 1);
+        assert!(composed.ends_with("fn main() {}\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be included")]
+    fn unknown_includes_panic() {
+        compose("#include \"missing.wgsl\"");
+    }
+}
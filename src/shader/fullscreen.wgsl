@@ -0,0 +1,6 @@
+// The output of the shared fullscreen vertex shader.
+struct FullscreenVertex {
+    @builtin(position) position: vec4<f32>,
+    // The texture coordinates with the origin in the top left corner.
+    @location(0) uv: vec2<f32>,
+};
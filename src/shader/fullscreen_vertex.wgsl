@@ -0,0 +1,34 @@
+// The vertex shaders which are shared by all effects.
+#include "fullscreen.wgsl"
+
+struct VertexInput {
+    @builtin(vertex_index) vertex_index: u32,
+};
+
+// Generate a triangle to fill the screen.
+// The approach is based on: https://stackoverflow.com/a/59739538/4593433.
+fn fullscreen_position(vertex_index: u32) -> vec2<f32> {
+    var fullscreen_vertecies = array(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(3.0, -1.0),
+        vec2<f32>(-1.0, 3.0)
+    );
+
+    return fullscreen_vertecies[vertex_index];
+}
+
+@vertex
+fn vs_main(in: VertexInput) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(fullscreen_position(in.vertex_index), 0.0, 1.0);
+}
+
+// Additionally outputs the texture coordinates, for fragment shaders which take a `FullscreenVertex`.
+@vertex
+fn vs_uv(in: VertexInput) -> FullscreenVertex {
+    let position = fullscreen_position(in.vertex_index);
+
+    var out: FullscreenVertex;
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    out.uv = vec2<f32>(position.x * 0.5 + 0.5, 0.5 - position.y * 0.5);
+    return out;
+}
@@ -0,0 +1,19 @@
+mod compose;
+pub(crate) use compose::compose;
+#[cfg(test)]
+pub(crate) mod validation;
+mod vertex_shader;
+pub(crate) use vertex_shader::VertexShader;
+
+/// Creates a shader module from a WGSL file, resolving its `#include` directives.
+///
+/// The path is relative to the calling file, like [wgpu::include_wgsl].
+macro_rules! include_composed_wgsl {
+    ($device:expr, $path:literal) => {
+        $device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some($path),
+            source: wgpu::ShaderSource::Wgsl($crate::shader::compose(include_str!($path)).into()),
+        })
+    };
+}
+pub(crate) use include_composed_wgsl;
@@ -0,0 +1,12 @@
+// Texture access helpers which are shared by the effects.
+
+// Returns the size of a single texel of `texture` in texture coordinates.
+fn texel_size(texture: texture_2d<f32>) -> vec2<f32> {
+    return 1.0 / vec2<f32>(textureDimensions(texture));
+}
+
+// Loads a texel of `texture`, clamping `pixel` to the edges of the texture.
+fn load_clamped(texture: texture_2d<f32>, pixel: vec2<i32>) -> vec4<f32> {
+    let size = vec2<i32>(textureDimensions(texture));
+    return textureLoad(texture, clamp(pixel, vec2<i32>(0), size - 1), 0);
+}
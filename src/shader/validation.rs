@@ -0,0 +1,295 @@
+use std::path::{Path, PathBuf};
+
+use naga::{
+    back::{glsl, hlsl, msl, spv},
+    valid::{Capabilities, ModuleInfo, ValidationFlags, Validator},
+    AddressSpace, ImageClass, ImageDimension, Module, ScalarKind, ShaderStage, TypeInner,
+};
+
+/// Parses and validates a WGSL `source` after resolving its includes.
+///
+/// Arguments:
+///
+/// * `path`: The path of the shader, which is used in error messages.
+/// * `source`: The WGSL source code of the shader.
+///
+/// Returns:
+///
+/// The parsed module and its validation info.
+///
+/// Panics if the shader cannot be parsed or is invalid.
+pub(crate) fn validate(path: &str, source: &str) -> (Module, ModuleInfo) {
+    let source = super::compose(source);
+    let module = naga::front::wgsl::parse_str(&source)
+        .unwrap_or_else(|error| panic!("{}", error.emit_to_string_with_path(&source, path)));
+    let info = Validator::new(ValidationFlags::all(), Capabilities::empty())
+        .validate(&module)
+        .unwrap_or_else(|error| panic!("{path} is invalid:\n{}", error.emit_to_string(&source)));
+
+    (module, info)
+}
+
+/// Checks that a shader contains `entry_point` for `stage` and that every resource
+/// used by the entry point is declared by the bind group layouts of the effect.
+///
+/// Arguments:
+///
+/// * `path`: The path of the shader, which is used in error messages.
+/// * `source`: The WGSL source code of the shader.
+/// * `stage`: The stage of the entry point.
+/// * `entry_point`: The name of the entry point.
+/// * `bind_group_layouts`: The entries of the bind group layouts of the pipeline, by group.
+///
+/// Panics if the entry point is missing or does not match the layouts.
+pub(crate) fn check_entry_point(
+    path: &str,
+    source: &str,
+    stage: ShaderStage,
+    entry_point: &str,
+    bind_group_layouts: &[&[wgpu::BindGroupLayoutEntry]],
+) {
+    let (module, info) = validate(path, source);
+    let Some(index) = module
+        .entry_points
+        .iter()
+        .position(|ep| ep.name == entry_point && ep.stage == stage)
+    else {
+        panic!("{path} has no {stage:?} entry point {entry_point:?}");
+    };
+
+    let function_info = info.get_entry_point(index);
+    for (handle, global) in module.global_variables.iter() {
+        let Some(binding) = &global.binding else {
+            continue;
+        };
+        if function_info[handle].is_empty() {
+            continue;
+        }
+
+        let name = global.name.as_deref().unwrap_or_default();
+        let entry = bind_group_layouts
+            .get(binding.group as usize)
+            .and_then(|entries| {
+                entries
+                    .iter()
+                    .find(|entry| entry.binding == binding.binding)
+            })
+            .unwrap_or_else(|| {
+                panic!(
+                    "{path}: {name} at group {} binding {} is used by {entry_point} \
+                     but not declared by the layout",
+                    binding.group, binding.binding
+                )
+            });
+
+        assert!(
+            entry.visibility.contains(stage_flags(stage)),
+            "{path}: {name} is not visible to the {stage:?} stage"
+        );
+        assert!(
+            is_compatible(
+                &module,
+                global.space,
+                &module.types[global.ty].inner,
+                &entry.ty
+            ),
+            "{path}: {name} does not match the layout entry {:?}",
+            entry.ty
+        );
+    }
+}
+
+/// Translates a validated module into the languages of every backend of `wgpu`,
+/// so that issues which are specific to a backend are caught as well.
+///
+/// Panics if the module cannot be translated.
+fn cross_compile(path: &str, module: &Module, info: &ModuleInfo) {
+    spv::write_vec(module, info, &spv::Options::default(), None)
+        .unwrap_or_else(|error| panic!("{path} cannot be translated to SPIR-V: {error}"));
+
+    msl::write_string(
+        module,
+        info,
+        &msl::Options::default(),
+        &msl::PipelineOptions::default(),
+    )
+    .unwrap_or_else(|error| panic!("{path} cannot be translated to MSL: {error}"));
+
+    let mut hlsl = String::new();
+    hlsl::Writer::new(&mut hlsl, &hlsl::Options::default())
+        .write(module, info)
+        .unwrap_or_else(|error| panic!("{path} cannot be translated to HLSL: {error}"));
+
+    // GLSL is translated per entry point, using the version supported by WebGL 2.
+    let options = glsl::Options {
+        version: glsl::Version::Embedded {
+            version: 300,
+            is_webgl: true,
+        },
+        ..Default::default()
+    };
+    for entry_point in &module.entry_points {
+        let pipeline_options = glsl::PipelineOptions {
+            shader_stage: entry_point.stage,
+            entry_point: entry_point.name.clone(),
+            multiview: None,
+        };
+        let mut glsl = String::new();
+        glsl::Writer::new(
+            &mut glsl,
+            module,
+            info,
+            &options,
+            &pipeline_options,
+            Default::default(),
+        )
+        .and_then(|mut writer| writer.write())
+        .unwrap_or_else(|error| {
+            panic!(
+                "{path} cannot be translated to GLSL for {}: {error}",
+                entry_point.name
+            )
+        });
+    }
+}
+
+/// Returns the shader stage flags of `stage`.
+fn stage_flags(stage: ShaderStage) -> wgpu::ShaderStages {
+    match stage {
+        ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+        ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+        ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+    }
+}
+
+/// Returns whether a global variable of type `inner` in address `space`
+/// can be bound using a layout entry of type `ty`.
+fn is_compatible(
+    module: &Module,
+    space: AddressSpace,
+    inner: &TypeInner,
+    ty: &wgpu::BindingType,
+) -> bool {
+    match (inner, ty) {
+        (
+            TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            },
+            wgpu::BindingType::Texture {
+                sample_type,
+                view_dimension,
+                multisampled,
+            },
+        ) => {
+            let dimension = match (dim, arrayed) {
+                (ImageDimension::D1, false) => wgpu::TextureViewDimension::D1,
+                (ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+                (ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+                (ImageDimension::D3, false) => wgpu::TextureViewDimension::D3,
+                (ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+                (ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray,
+                _ => return false,
+            };
+            let class_matches = match (class, sample_type) {
+                (ImageClass::Sampled { kind, multi }, sample_type) => {
+                    *multi == *multisampled
+                        && matches!(
+                            (kind, sample_type),
+                            (ScalarKind::Float, wgpu::TextureSampleType::Float { .. })
+                                | (ScalarKind::Sint, wgpu::TextureSampleType::Sint)
+                                | (ScalarKind::Uint, wgpu::TextureSampleType::Uint)
+                        )
+                }
+                (ImageClass::Depth { multi }, wgpu::TextureSampleType::Depth) => {
+                    *multi == *multisampled
+                }
+                _ => false,
+            };
+
+            dimension == *view_dimension && class_matches
+        }
+        (TypeInner::Sampler { comparison }, wgpu::BindingType::Sampler(sampler_type)) => {
+            *comparison == (*sampler_type == wgpu::SamplerBindingType::Comparison)
+        }
+        (
+            inner,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                min_binding_size,
+                ..
+            },
+        ) => {
+            let size = u64::from(inner.size(&module.constants));
+            space == AddressSpace::Uniform
+                && min_binding_size.is_some_and(|min_size| min_size.get() >= size)
+        }
+        _ => false,
+    }
+}
+
+/// Collects the paths of all WGSL files in `dir` and its subdirectories.
+fn collect_shaders(dir: &Path, shaders: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).expect("Failed to read the source directory") {
+        let path = entry.expect("Failed to read the source directory").path();
+        if path.is_dir() {
+            collect_shaders(&path, shaders);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "wgsl")
+        {
+            shaders.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_shader_is_valid_on_all_backends() {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut shaders = Vec::new();
+        collect_shaders(&src, &mut shaders);
+        assert!(!shaders.is_empty());
+
+        for shader in shaders {
+            let path = shader.strip_prefix(&src).unwrap().display().to_string();
+            let source = std::fs::read_to_string(&shader).expect("Failed to read the shader");
+            let (module, info) = validate(&path, &source);
+            cross_compile(&path, &module, &info);
+        }
+    }
+
+    #[test]
+    fn fullscreen_vertex_shader_has_no_bindings() {
+        let source = include_str!("fullscreen_vertex.wgsl");
+        for entry_point in ["vs_main", "vs_uv"] {
+            check_entry_point(
+                "shader/fullscreen_vertex.wgsl",
+                source,
+                ShaderStage::Vertex,
+                entry_point,
+                &[],
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not declared by the layout")]
+    fn missing_bindings_are_detected() {
+        check_entry_point(
+            "missing.wgsl",
+            "@group(0) @binding(1) var input_texture : texture_2d<f32>;
+            @fragment
+            fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+                return textureLoad(input_texture, vec2<i32>(pos.xy), 0);
+            }",
+            ShaderStage::Fragment,
+            "fs_main",
+            &[&[]],
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+/// The fullscreen vertex shaders which are in use by at least one effect.
+///
+/// The shaders are identified by the id of their device, which stays the same when the
+/// device is moved and contains an epoch, so that it is not reused by later devices.
+static FULLSCREEN_SHADERS: Mutex<Vec<(wgpu::Id, Weak<wgpu::ShaderModule>)>> =
+    Mutex::new(Vec::new());
+
+/// A handle to the vertex shader in `fullscreen_vertex.wgsl`, which draws a single
+/// triangle covering the whole frame.
+///
+/// The shader module is shared by all effects on a device and is kept alive
+/// as long as any of these effects holds a handle to it.
+pub(crate) struct VertexShader {
+    module: Arc<wgpu::ShaderModule>,
+}
+
+impl VertexShader {
+    /// Returns the fullscreen vertex shader of `device`, which is only created
+    /// if no other effect on the same device uses it already.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    ///
+    /// Returns:
+    ///
+    /// The handle to the shared vertex shader.
+    pub(crate) fn fullscreen(device: &wgpu::Device) -> Self {
+        let id = device.global_id();
+        let mut shaders = FULLSCREEN_SHADERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        shaders.retain(|(_, module)| module.strong_count() > 0);
+
+        if let Some(module) = shaders
+            .iter()
+            .find(|(device_id, _)| *device_id == id)
+            .and_then(|(_, module)| module.upgrade())
+        {
+            return Self { module };
+        }
+
+        let module = Arc::new(super::include_composed_wgsl!(
+            device,
+            "fullscreen_vertex.wgsl"
+        ));
+        shaders.push((id, Arc::downgrade(&module)));
+
+        Self { module }
+    }
+
+    /// Returns the vertex state which draws the fullscreen triangle using three vertices.
+    ///
+    /// Only the position is passed on, so the fragment shader must take `@builtin(position)`.
+    pub(crate) fn state(&self) -> wgpu::VertexState<'_> {
+        wgpu::VertexState {
+            module: &self.module,
+            entry_point: "vs_main",
+            buffers: &[],
+        }
+    }
+
+    /// Returns the vertex state which draws the fullscreen triangle, like [VertexShader::state],
+    /// but passes on the texture coordinates as well.
+    ///
+    /// The fragment shader must take a `FullscreenVertex` declared in `fullscreen.wgsl`.
+    #[cfg(any(feature = "bloom", feature = "blur", feature = "dof"))]
+    pub(crate) fn state_with_uv(&self) -> wgpu::VertexState<'_> {
+        wgpu::VertexState {
+            module: &self.module,
+            entry_point: "vs_uv",
+            buffers: &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_on_a_device_share_the_module() {
+        let Some((device, _queue)) = crate::test_device::device() else {
+            return;
+        };
+
+        let first = VertexShader::fullscreen(&device);
+        // Moving the device must not create another module.
+        let device = Box::new(device);
+        let second = VertexShader::fullscreen(&device);
+        assert!(Arc::ptr_eq(&first.module, &second.module));
+
+        let module = Arc::downgrade(&first.module);
+        drop((first, second));
+        assert_eq!(module.strong_count(), 0);
+    }
+}
@@ -0,0 +1,5 @@
+mod smaa_effect;
+pub use smaa_effect::{SmaaEffect, SmaaFrame};
+mod smaa_effect_inner;
+mod smaa_settings;
+pub use smaa_settings::{SmaaEdgeDetection, SmaaSettings};
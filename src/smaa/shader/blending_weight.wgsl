@@ -0,0 +1,202 @@
+// The blending weight calculation pass of SMAA 1x.
+// Based on the reference implementation: https://github.com/iryoku/smaa.
+//
+// Diagonal pattern detection is not supported, which corresponds
+// to the low and medium presets of the reference implementation.
+
+struct SmaaSettings {
+    threshold: f32,
+    max_search_steps: u32,
+    corner_rounding: f32,
+};
+
+@group(0) @binding(0) var edges_texture : texture_2d<f32>;
+@group(0) @binding(1) var area_texture : texture_2d<f32>;
+@group(0) @binding(2) var search_texture : texture_2d<f32>;
+@group(0) @binding(3) var linear_sampler : sampler;
+@group(0) @binding(4) var point_sampler : sampler;
+@group(0) @binding(5) var<uniform> settings : SmaaSettings;
+
+// The maximum distance (in pixels) which is stored in the area texture.
+const AREA_TEXTURE_MAX_DISTANCE = 16.0;
+// The size of the area texture in pixels.
+const AREA_TEXTURE_SIZE = vec2<f32>(80.0, 80.0);
+// The size of the search texture before and after it was cropped.
+const SEARCH_TEXTURE_SIZE = vec2<f32>(66.0, 33.0);
+const SEARCH_TEXTURE_PACKED_SIZE = vec2<f32>(64.0, 16.0);
+
+fn sample_edges(uv: vec2<f32>) -> vec2<f32> {
+    return textureSampleLevel(edges_texture, linear_sampler, uv, 0.0).rg;
+}
+
+// Looks up how far the last step of a search went beyond the end of the edge.
+fn search_length(e: vec2<f32>, offset: f32) -> f32 {
+    // The texture is flipped vertically, with left and right cases taking half
+    // of the space horizontally.
+    var scale = SEARCH_TEXTURE_SIZE * vec2<f32>(0.5, -1.0);
+    var bias = SEARCH_TEXTURE_SIZE * vec2<f32>(offset, 1.0);
+
+    // Scale and bias to access texel centers.
+    scale += vec2<f32>(-1.0, 1.0);
+    bias += vec2<f32>(0.5, -0.5);
+
+    // The packed size is used, because the texture is cropped.
+    let uv = (scale * e + bias) / SEARCH_TEXTURE_PACKED_SIZE;
+
+    return textureSampleLevel(search_texture, point_sampler, uv, 0.0).r;
+}
+
+fn search_x_left(start: vec2<f32>, end: f32, texel: vec2<f32>) -> f32 {
+    var uv = start;
+    var e = vec2<f32>(0.0, 1.0);
+    while (uv.x > end && e.g > 0.8281 && e.r == 0.0) {
+        e = sample_edges(uv);
+        uv -= vec2<f32>(2.0, 0.0) * texel;
+    }
+
+    let offset = -(255.0 / 127.0) * search_length(e, 0.0) + 3.25;
+    return texel.x * offset + uv.x;
+}
+
+fn search_x_right(start: vec2<f32>, end: f32, texel: vec2<f32>) -> f32 {
+    var uv = start;
+    var e = vec2<f32>(0.0, 1.0);
+    while (uv.x < end && e.g > 0.8281 && e.r == 0.0) {
+        e = sample_edges(uv);
+        uv += vec2<f32>(2.0, 0.0) * texel;
+    }
+
+    let offset = -(255.0 / 127.0) * search_length(e, 0.5) + 3.25;
+    return -texel.x * offset + uv.x;
+}
+
+fn search_y_up(start: vec2<f32>, end: f32, texel: vec2<f32>) -> f32 {
+    var uv = start;
+    var e = vec2<f32>(1.0, 0.0);
+    while (uv.y > end && e.r > 0.8281 && e.g == 0.0) {
+        e = sample_edges(uv);
+        uv -= vec2<f32>(0.0, 2.0) * texel;
+    }
+
+    let offset = -(255.0 / 127.0) * search_length(e.gr, 0.0) + 3.25;
+    return texel.y * offset + uv.y;
+}
+
+fn search_y_down(start: vec2<f32>, end: f32, texel: vec2<f32>) -> f32 {
+    var uv = start;
+    var e = vec2<f32>(1.0, 0.0);
+    while (uv.y < end && e.r > 0.8281 && e.g == 0.0) {
+        e = sample_edges(uv);
+        uv += vec2<f32>(0.0, 2.0) * texel;
+    }
+
+    let offset = -(255.0 / 127.0) * search_length(e.gr, 0.5) + 3.25;
+    return -texel.y * offset + uv.y;
+}
+
+// Looks up the coverage areas for the given distances and crossing edges.
+fn area(dist: vec2<f32>, e1: f32, e2: f32) -> vec2<f32> {
+    // Rounding prevents precision errors of bilinear filtering.
+    let texel_pos = AREA_TEXTURE_MAX_DISTANCE * round(4.0 * vec2<f32>(e1, e2)) + dist;
+    let uv = (texel_pos + 0.5) / AREA_TEXTURE_SIZE;
+
+    return textureSampleLevel(area_texture, linear_sampler, uv, 0.0).rg;
+}
+
+// Reduces the blending of pixels close to corners, so that sharp corners are preserved.
+fn detect_horizontal_corner_pattern(
+    weights: vec2<f32>,
+    left: vec2<f32>,
+    right: vec2<f32>,
+    d: vec2<f32>,
+    texel: vec2<f32>,
+) -> vec2<f32> {
+    let left_right = step(d, d.yx);
+    let rounding = (1.0 - settings.corner_rounding) * left_right / (left_right.x + left_right.y);
+
+    var factor = vec2<f32>(1.0);
+    factor.x -= rounding.x * sample_edges(left + vec2<f32>(0.0, 1.0) * texel).r;
+    factor.x -= rounding.y * sample_edges(right + vec2<f32>(1.0, 1.0) * texel).r;
+    factor.y -= rounding.x * sample_edges(left + vec2<f32>(0.0, -2.0) * texel).r;
+    factor.y -= rounding.y * sample_edges(right + vec2<f32>(1.0, -2.0) * texel).r;
+
+    return weights * clamp(factor, vec2<f32>(0.0), vec2<f32>(1.0));
+}
+
+fn detect_vertical_corner_pattern(
+    weights: vec2<f32>,
+    top: vec2<f32>,
+    bottom: vec2<f32>,
+    d: vec2<f32>,
+    texel: vec2<f32>,
+) -> vec2<f32> {
+    let left_right = step(d, d.yx);
+    let rounding = (1.0 - settings.corner_rounding) * left_right / (left_right.x + left_right.y);
+
+    var factor = vec2<f32>(1.0);
+    factor.x -= rounding.x * sample_edges(top + vec2<f32>(1.0, 0.0) * texel).g;
+    factor.x -= rounding.y * sample_edges(bottom + vec2<f32>(1.0, 1.0) * texel).g;
+    factor.y -= rounding.x * sample_edges(top + vec2<f32>(-2.0, 0.0) * texel).g;
+    factor.y -= rounding.y * sample_edges(bottom + vec2<f32>(-2.0, 1.0) * texel).g;
+
+    return weights * clamp(factor, vec2<f32>(0.0), vec2<f32>(1.0));
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let size = vec2<f32>(textureDimensions(edges_texture));
+    let texel = 1.0 / size;
+    let uv = pos.xy * texel;
+    let search_distance = 2.0 * f32(settings.max_search_steps);
+
+    var weights = vec4<f32>(0.0);
+    let e = textureSampleLevel(edges_texture, linear_sampler, uv, 0.0).rg;
+
+    // Edge at the top of the pixel.
+    if (e.g > 0.0) {
+        let start_left = uv + vec2<f32>(-0.25, -0.125) * texel;
+        let start_right = uv + vec2<f32>(1.25, -0.125) * texel;
+        let crossing_y = uv.y - 0.25 * texel.y;
+
+        let left = search_x_left(start_left, start_left.x - search_distance * texel.x, texel);
+        let right = search_x_right(start_right, start_right.x + search_distance * texel.x, texel);
+
+        let d = abs(round(size.x * vec2<f32>(left, right) - pos.x));
+        let e1 = sample_edges(vec2<f32>(left, crossing_y)).r;
+        let e2 = sample_edges(vec2<f32>(right + texel.x, crossing_y)).r;
+
+        let rg = area(sqrt(d), e1, e2);
+        weights = vec4<f32>(detect_horizontal_corner_pattern(
+            rg,
+            vec2<f32>(left, uv.y),
+            vec2<f32>(right, uv.y),
+            d,
+            texel
+        ), weights.ba);
+    }
+
+    // Edge at the left of the pixel.
+    if (e.r > 0.0) {
+        let start_top = uv + vec2<f32>(-0.125, -0.25) * texel;
+        let start_bottom = uv + vec2<f32>(-0.125, 1.25) * texel;
+        let crossing_x = uv.x - 0.25 * texel.x;
+
+        let top = search_y_up(start_top, start_top.y - search_distance * texel.y, texel);
+        let bottom = search_y_down(start_bottom, start_bottom.y + search_distance * texel.y, texel);
+
+        let d = abs(round(size.y * vec2<f32>(top, bottom) - pos.y));
+        let e1 = sample_edges(vec2<f32>(crossing_x, top)).g;
+        let e2 = sample_edges(vec2<f32>(crossing_x, bottom + texel.y)).g;
+
+        let ba = area(sqrt(d), e1, e2);
+        weights = vec4<f32>(weights.rg, detect_vertical_corner_pattern(
+            ba,
+            vec2<f32>(uv.x, top),
+            vec2<f32>(uv.x, bottom),
+            d,
+            texel
+        ));
+    }
+
+    return weights;
+}
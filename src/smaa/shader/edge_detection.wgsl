@@ -0,0 +1,114 @@
+// The edge detection pass of SMAA 1x.
+// Based on the reference implementation: https://github.com/iryoku/smaa.
+
+#include "color.wgsl"
+#include "sampling.wgsl"
+
+struct SmaaSettings {
+    threshold: f32,
+    max_search_steps: u32,
+    corner_rounding: f32,
+};
+
+@group(0) @binding(0) var color_texture : texture_2d<f32>;
+// Bound as a float texture, because loading from depth textures is not supported on every backend.
+@group(0) @binding(1) var depth_texture : texture_2d<f32>;
+@group(0) @binding(2) var<uniform> settings : SmaaSettings;
+
+// Scales the threshold for edges which are much weaker than the strongest
+// neighboring edge, so that they are not detected.
+const LOCAL_CONTRAST_ADAPTATION_FACTOR = 2.0;
+
+fn load_color(pixel: vec2<i32>) -> vec3<f32> {
+    return load_clamped(color_texture, pixel).rgb;
+}
+
+fn load_luma(pixel: vec2<i32>) -> f32 {
+    return luma_rec709(load_color(pixel));
+}
+
+fn load_depth(pixel: vec2<i32>) -> f32 {
+    return load_clamped(depth_texture, pixel).r;
+}
+
+fn color_delta(a: vec3<f32>, b: vec3<f32>) -> f32 {
+    let t = abs(a - b);
+    return max(max(t.r, t.g), t.b);
+}
+
+// Suppresses edges which are much weaker than the strongest edge around them.
+fn adapt_to_local_contrast(edges: vec2<f32>, delta: vec2<f32>, max_delta: f32) -> vec2<f32> {
+    return edges * step(vec2<f32>(max_delta), LOCAL_CONTRAST_ADAPTATION_FACTOR * delta);
+}
+
+@fragment
+fn fs_luma(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(pos.xy);
+
+    let l = load_luma(pixel);
+    let l_left = load_luma(pixel + vec2<i32>(-1, 0));
+    let l_top = load_luma(pixel + vec2<i32>(0, -1));
+
+    let delta = abs(l - vec2<f32>(l_left, l_top));
+    let edges = step(vec2<f32>(settings.threshold), delta);
+    if (dot(edges, vec2<f32>(1.0)) == 0.0) {
+        discard;
+    }
+
+    let l_right = load_luma(pixel + vec2<i32>(1, 0));
+    let l_bottom = load_luma(pixel + vec2<i32>(0, 1));
+    let l_left_left = load_luma(pixel + vec2<i32>(-2, 0));
+    let l_top_top = load_luma(pixel + vec2<i32>(0, -2));
+
+    let max_delta = max(
+        max(delta, abs(l - vec2<f32>(l_right, l_bottom))),
+        abs(vec2<f32>(l_left, l_top) - vec2<f32>(l_left_left, l_top_top))
+    );
+
+    return vec4<f32>(adapt_to_local_contrast(edges, delta, max(max_delta.x, max_delta.y)), 0.0, 0.0);
+}
+
+@fragment
+fn fs_color(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(pos.xy);
+
+    let c = load_color(pixel);
+    let c_left = load_color(pixel + vec2<i32>(-1, 0));
+    let c_top = load_color(pixel + vec2<i32>(0, -1));
+
+    let delta = vec2<f32>(color_delta(c, c_left), color_delta(c, c_top));
+    let edges = step(vec2<f32>(settings.threshold), delta);
+    if (dot(edges, vec2<f32>(1.0)) == 0.0) {
+        discard;
+    }
+
+    let c_right = load_color(pixel + vec2<i32>(1, 0));
+    let c_bottom = load_color(pixel + vec2<i32>(0, 1));
+    let c_left_left = load_color(pixel + vec2<i32>(-2, 0));
+    let c_top_top = load_color(pixel + vec2<i32>(0, -2));
+
+    let max_delta = max(
+        max(delta, vec2<f32>(color_delta(c, c_right), color_delta(c, c_bottom))),
+        vec2<f32>(color_delta(c_left, c_left_left), color_delta(c_top, c_top_top))
+    );
+
+    return vec4<f32>(adapt_to_local_contrast(edges, delta, max(max_delta.x, max_delta.y)), 0.0, 0.0);
+}
+
+@fragment
+fn fs_depth(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(pos.xy);
+
+    let d = load_depth(pixel);
+    let d_left = load_depth(pixel + vec2<i32>(-1, 0));
+    let d_top = load_depth(pixel + vec2<i32>(0, -1));
+
+    // Depth edges use a lower threshold, because depth differences are usually small.
+    let delta = abs(d - vec2<f32>(d_left, d_top));
+    let edges = step(vec2<f32>(0.1 * settings.threshold), delta);
+    if (dot(edges, vec2<f32>(1.0)) == 0.0) {
+        discard;
+    }
+
+    return vec4<f32>(edges, 0.0, 0.0);
+}
@@ -0,0 +1,40 @@
+// The neighborhood blending pass of SMAA 1x.
+// Based on the reference implementation: https://github.com/iryoku/smaa.
+
+#include "sampling.wgsl"
+
+@group(0) @binding(0) var input_texture : texture_2d<f32>;
+@group(0) @binding(1) var blend_texture : texture_2d<f32>;
+@group(0) @binding(2) var linear_sampler : sampler;
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let texel = texel_size(input_texture);
+    let uv = pos.xy * texel;
+
+    // Fetch the blending weights of the current pixel and
+    // the weights stored in its right and bottom neighbors.
+    let current = textureSampleLevel(blend_texture, linear_sampler, uv, 0.0);
+    let right = textureSampleLevel(blend_texture, linear_sampler, uv + vec2<f32>(texel.x, 0.0), 0.0).a;
+    let bottom = textureSampleLevel(blend_texture, linear_sampler, uv + vec2<f32>(0.0, texel.y), 0.0).g;
+    let a = vec4<f32>(right, bottom, current.z, current.x);
+
+    if (dot(a, vec4<f32>(1.0)) < 1e-5) {
+        return textureSampleLevel(input_texture, linear_sampler, uv, 0.0);
+    }
+
+    // Blend either horizontally or vertically, depending on which weights are larger.
+    let horizontal = max(a.x, a.z) > max(a.y, a.w);
+    var blending_offset = vec4<f32>(0.0, a.y, 0.0, a.w);
+    var blending_weight = a.yw;
+    if (horizontal) {
+        blending_offset = vec4<f32>(a.x, 0.0, a.z, 0.0);
+        blending_weight = a.xz;
+    }
+    blending_weight /= dot(blending_weight, vec2<f32>(1.0));
+
+    let blending_uv = blending_offset * vec4<f32>(texel, -texel) + uv.xyxy;
+
+    return blending_weight.x * textureSampleLevel(input_texture, linear_sampler, blending_uv.xy, 0.0)
+        + blending_weight.y * textureSampleLevel(input_texture, linear_sampler, blending_uv.zw, 0.0);
+}
@@ -0,0 +1,73 @@
+use crate::{
+    effect_frame::{validate_depth_texture, FrameInputs},
+    effect_state::{impl_effect, EffectState},
+    EffectFrame, Error,
+};
+
+use super::{smaa_effect_inner::SmaaEffectInner, SmaaSettings};
+
+/// Smooths jagged edges using subpixel morphological anti-aliasing (SMAA 1x).
+///
+/// The effect consists of three passes: Edge detection, blending weight
+/// calculation and neighborhood blending.
+///
+/// When using [crate::smaa::SmaaEdgeDetection::Depth] without a depth texture, e.g. in
+/// [SmaaEffect::apply], edges are detected using the luma of the frame instead.
+/// Use [SmaaEffect::apply_with_depth] to detect edges using the depth of the scene.
+pub struct SmaaEffect {
+    pub(super) state: EffectState<SmaaEffectInner>,
+    pub(super) settings: SmaaSettings,
+}
+
+/// The frame into which the scene is rendered before the [SmaaEffect] is resolved.
+pub type SmaaFrame<'a> = EffectFrame<'a, SmaaEffect>;
+
+impl SmaaEffect {
+    /// Applies the anti-aliasing effect to an arbitrary `input_view`
+    /// using the depth of the scene for edge detection.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `input_view`: The view which will be anti-aliased.
+    /// * `depth_texture`: The depth texture which belongs to `input_view`.
+    /// * `output_view`: The view into which the result is written.
+    ///
+    /// Returns:
+    ///
+    /// An [Error] if the depth texture is multisampled or cannot be bound,
+    /// in which case nothing is recorded.
+    pub fn apply_with_depth(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        depth_texture: &wgpu::Texture,
+        output_view: &wgpu::TextureView,
+    ) -> Result<(), Error> {
+        validate_depth_texture(depth_texture)?;
+
+        let inputs = FrameInputs {
+            depth_texture: Some(depth_texture),
+            ..Default::default()
+        };
+        self.state
+            .apply(device, encoder, input_view, &inputs, output_view);
+
+        Ok(())
+    }
+
+    /// Creates the resources of the effect using the current size, format and settings.
+    fn create_inner(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> SmaaEffectInner {
+        SmaaEffectInner::new(
+            device,
+            queue,
+            &self.state.allocation_size(),
+            self.state.format(),
+            &self.settings,
+        )
+    }
+}
+
+impl_effect!(SmaaEffect, "anti-aliasing", SmaaSettings, queue, depth);
@@ -0,0 +1,753 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    render_target::create_texture_view,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::{SmaaEdgeDetection, SmaaSettings};
+
+/// The precomputed coverage areas for all orthogonal edge patterns (`Rg8Unorm`, 80x80).
+///
+/// This is the orthogonal, non-offset part of the area texture generated by `AreaTex.py`
+/// of the reference implementation, as diagonal patterns and SMAA T2x/S2x/4x are not supported.
+const AREA_TEXTURE: &[u8] = include_bytes!("texture/area_tex.bin");
+
+/// The precomputed search distance corrections (`R8Unorm`, 64x16).
+///
+/// This is the texture generated by `SearchTex.py` of the reference implementation.
+const SEARCH_TEXTURE: &[u8] = include_bytes!("texture/search_tex.bin");
+
+/// The entries of the bind group layout of the blending weight calculation pass,
+/// which match the bindings of `shader/blending_weight.wgsl`.
+const WEIGHT_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 6] = [
+    texture_layout_entry(0),
+    texture_layout_entry(1),
+    texture_layout_entry(2),
+    sampler_layout_entry(3),
+    sampler_layout_entry(4),
+    settings_layout_entry(5),
+];
+
+/// The entries of the bind group layout of the neighborhood blending pass,
+/// which match the bindings of `shader/neighborhood_blending.wgsl`.
+const BLEND_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 3] = [
+    texture_layout_entry(0),
+    texture_layout_entry(1),
+    sampler_layout_entry(2),
+];
+
+/// Wraps the internal fields and implementation
+/// of a [crate::smaa::SmaaEffect].
+pub(super) struct SmaaEffectInner {
+    pub(super) edge_pass: EdgePass,
+    pub(super) luma_edge_pass: Option<EdgePass>,
+    pub(super) weight_pipeline: wgpu::RenderPipeline,
+    pub(super) blend_pipeline: wgpu::RenderPipeline,
+    pub(super) vertex_shader: VertexShader,
+    pub(super) weight_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) blend_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) linear_sampler: wgpu::Sampler,
+    pub(super) point_sampler: wgpu::Sampler,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) area_texture: wgpu::TextureView,
+    pub(super) search_texture: wgpu::TextureView,
+    pub(super) edges: wgpu::TextureView,
+    pub(super) weights: wgpu::TextureView,
+    pub(super) weight_bind_group: wgpu::BindGroup,
+    pub(super) blend_bind_groups: BindGroupCache,
+}
+
+/// The edge detection pass of a [SmaaEffectInner] for one of the inputs which can be used to detect edges.
+pub(super) struct EdgePass {
+    pub(super) pipeline: wgpu::RenderPipeline,
+    pub(super) bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) bind_groups: BindGroupCache,
+    pub(super) edge_detection: SmaaEdgeDetection,
+}
+
+impl EdgePass {
+    /// Creates a new [EdgePass].
+    ///
+    /// The bind group layout and fragment shader depend on the input which is used to detect edges.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `vertex_shader`: The shared fullscreen vertex shader.
+    /// * `edge_detection`: The input which is used to detect edges.
+    ///
+    /// Returns:
+    ///
+    /// The new [EdgePass].
+    fn new(
+        device: &wgpu::Device,
+        vertex_shader: &VertexShader,
+        edge_detection: SmaaEdgeDetection,
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &edge_bind_group_layout_entries(edge_detection),
+        });
+
+        let edge_shader = include_composed_wgsl!(device, "shader/edge_detection.wgsl");
+
+        let pipeline = create_pipeline(
+            device,
+            &bind_group_layout,
+            vertex_shader,
+            &edge_shader,
+            match edge_detection {
+                SmaaEdgeDetection::Luma => "fs_luma",
+                SmaaEdgeDetection::Color => "fs_color",
+                SmaaEdgeDetection::Depth => "fs_depth",
+            },
+            wgpu::TextureFormat::Rg8Unorm,
+        );
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            bind_groups: BindGroupCache::new(),
+            edge_detection,
+        }
+    }
+
+    /// Records the edge detection pass into `encoder`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the pass is recorded.
+    /// * `edge_source`: The color or depth view in which edges are detected.
+    /// * `settings_buffer`: The uniform buffer containing the [SmaaSettings].
+    /// * `edges`: The view into which the detected edges are written.
+    fn render(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        edge_source: &wgpu::TextureView,
+        settings_buffer: &wgpu::Buffer,
+        edges: &wgpu::TextureView,
+    ) {
+        let bind_group = self.bind_groups.get_or_insert_with(edge_source, || {
+            create_edge_bind_group(
+                device,
+                &self.bind_group_layout,
+                self.edge_detection,
+                edge_source,
+                settings_buffer,
+            )
+        });
+        render(encoder, &self.pipeline, bind_group, edges);
+    }
+}
+
+impl SmaaEffectInner {
+    /// Creates a new [SmaaEffectInner].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `queue`: The queue which is used to upload the lookup textures.
+    /// * `size`: The size of the frame which will later be processed.
+    /// * `format`: The texture format of the post-processed frame.
+    /// * `settings`: The settings of the anti-aliasing.
+    ///
+    /// Returns:
+    ///
+    /// The new [SmaaEffectInner].
+    pub(super) fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: &wgpu::Extent3d,
+        format: EffectFormat,
+        settings: &SmaaSettings,
+    ) -> Self {
+        let edges = create_texture_view(device, size, wgpu::TextureFormat::Rg8Unorm);
+        let weights = create_texture_view(device, size, wgpu::TextureFormat::Rgba8Unorm);
+
+        let area_texture = create_lookup_texture(
+            device,
+            queue,
+            80,
+            80,
+            wgpu::TextureFormat::Rg8Unorm,
+            AREA_TEXTURE,
+        );
+        let search_texture = create_lookup_texture(
+            device,
+            queue,
+            64,
+            16,
+            wgpu::TextureFormat::R8Unorm,
+            SEARCH_TEXTURE,
+        );
+
+        let linear_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let point_sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &settings.to_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let weight_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &WEIGHT_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let blend_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &BLEND_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let weight_shader = include_composed_wgsl!(device, "shader/blending_weight.wgsl");
+        let blend_shader = include_composed_wgsl!(device, "shader/neighborhood_blending.wgsl");
+
+        let edge_pass = EdgePass::new(device, &vertex_shader, settings.edge_detection);
+        let weight_pipeline = create_pipeline(
+            device,
+            &weight_bind_group_layout,
+            &vertex_shader,
+            &weight_shader,
+            "fs_main",
+            wgpu::TextureFormat::Rgba8Unorm,
+        );
+        let blend_pipeline = create_pipeline(
+            device,
+            &blend_bind_group_layout,
+            &vertex_shader,
+            &blend_shader,
+            "fs_main",
+            format.output,
+        );
+
+        let weight_bind_group = create_weight_bind_group(
+            device,
+            &weight_bind_group_layout,
+            &edges,
+            &area_texture,
+            &search_texture,
+            &linear_sampler,
+            &point_sampler,
+            &settings_buffer,
+        );
+
+        Self {
+            edge_pass,
+            luma_edge_pass: None,
+            weight_pipeline,
+            blend_pipeline,
+            vertex_shader,
+            weight_bind_group_layout,
+            blend_bind_group_layout,
+            linear_sampler,
+            point_sampler,
+            settings_buffer,
+            area_texture,
+            search_texture,
+            edges,
+            weights,
+            weight_bind_group,
+            blend_bind_groups: BindGroupCache::new(),
+        }
+    }
+
+    /// Uploads new settings into the uniform buffer.
+    ///
+    /// If the edge detection changes, the edge detection pipeline is recreated.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the anti-aliasing.
+    pub(super) fn set_settings(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &SmaaSettings,
+    ) {
+        queue.write_buffer(&self.settings_buffer, 0, &settings.to_bytes());
+
+        if settings.edge_detection != self.edge_pass.edge_detection {
+            self.edge_pass = EdgePass::new(device, &self.vertex_shader, settings.edge_detection);
+        }
+    }
+}
+
+impl EffectInner for SmaaEffectInner {
+    /// Resizes the [SmaaEffectInner] after creation.
+    ///
+    /// This recreates the intermediate edge and blending weight textures.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size to which the effect should be resized.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        self.edges = create_texture_view(device, size, wgpu::TextureFormat::Rg8Unorm);
+        self.weights = create_texture_view(device, size, wgpu::TextureFormat::Rgba8Unorm);
+
+        self.weight_bind_group = create_weight_bind_group(
+            device,
+            &self.weight_bind_group_layout,
+            &self.edges,
+            &self.area_texture,
+            &self.search_texture,
+            &self.linear_sampler,
+            &self.point_sampler,
+            &self.settings_buffer,
+        );
+        self.edge_pass.bind_groups.clear();
+        if let Some(ref mut luma_edge_pass) = self.luma_edge_pass {
+            luma_edge_pass.bind_groups.clear();
+        }
+        self.blend_bind_groups.clear();
+    }
+
+    /// Records all three anti-aliasing passes which read from `input_view`
+    /// and write into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the passes are recorded.
+    /// * `input_view`: The view which will be anti-aliased.
+    /// * `inputs`: The additional inputs, where the depth of the scene
+    ///   is only used by [SmaaEdgeDetection::Depth]. Without a depth view,
+    ///   edges are detected using [SmaaEdgeDetection::Luma] instead.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        // Without a depth texture, e.g. within an effect chain, the frame is still
+        // anti-aliased by detecting edges in its luma instead of passing it through.
+        let (edge_pass, edge_source) = match (self.edge_pass.edge_detection, inputs.depth_view) {
+            (SmaaEdgeDetection::Depth, Some(depth_view)) => (&mut self.edge_pass, depth_view),
+            (SmaaEdgeDetection::Depth, None) => {
+                let vertex_shader = &self.vertex_shader;
+                let luma_edge_pass = self.luma_edge_pass.get_or_insert_with(|| {
+                    EdgePass::new(device, vertex_shader, SmaaEdgeDetection::Luma)
+                });
+                (luma_edge_pass, input_view)
+            }
+            _ => (&mut self.edge_pass, input_view),
+        };
+        edge_pass.render(
+            device,
+            encoder,
+            edge_source,
+            &self.settings_buffer,
+            &self.edges,
+        );
+
+        render(
+            encoder,
+            &self.weight_pipeline,
+            &self.weight_bind_group,
+            &self.weights,
+        );
+
+        let blend_bind_group = self.blend_bind_groups.get_or_insert_with(input_view, || {
+            create_blend_bind_group(
+                device,
+                &self.blend_bind_group_layout,
+                input_view,
+                &self.weights,
+                &self.linear_sampler,
+            )
+        });
+        render(encoder, &self.blend_pipeline, blend_bind_group, output_view);
+    }
+}
+
+/// Creates one of the precomputed lookup textures and uploads its `data`.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `queue`: The queue which is used to upload the data.
+/// * `width`: The width of the texture.
+/// * `height`: The height of the texture.
+/// * `format`: The format of the texture.
+/// * `data`: The tightly packed texels of the texture.
+///
+/// Returns:
+///
+/// The view of the new texture.
+fn create_lookup_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    data: &[u8],
+) -> wgpu::TextureView {
+    device
+        .create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                label: None,
+                view_formats: &[],
+            },
+            data,
+        )
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// Creates the layout entry of a filterable 2D float texture.
+const fn texture_layout_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+/// Creates the layout entry of a filtering sampler.
+const fn sampler_layout_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    }
+}
+
+/// Creates the layout entry of the uniform buffer containing the [SmaaSettings].
+const fn settings_layout_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(SmaaSettings::SIZE as u64),
+        },
+        count: None,
+    }
+}
+
+/// Returns the entries of the bind group layout of the edge detection pass,
+/// which match the bindings of `shader/edge_detection.wgsl` used by the fragment shader
+/// of `edge_detection`.
+fn edge_bind_group_layout_entries(
+    edge_detection: SmaaEdgeDetection,
+) -> [wgpu::BindGroupLayoutEntry; 2] {
+    [
+        match edge_detection {
+            SmaaEdgeDetection::Depth => wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            _ => wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+        },
+        settings_layout_entry(2),
+    ]
+}
+
+/// Creates the pipeline of one of the anti-aliasing passes.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `bind_group_layout`: The layout of the only bind group of the pass.
+/// * `vertex_shader`: The shared fullscreen vertex shader.
+/// * `shader`: The shader module of the pass.
+/// * `fragment_entry_point`: The name of the fragment shader function.
+/// * `format`: The format of the texture into which the pass renders.
+///
+/// Returns:
+///
+/// The new pipeline.
+fn create_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    vertex_shader: &VertexShader,
+    shader: &wgpu::ShaderModule,
+    fragment_entry_point: &str,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: vertex_shader.state(),
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: fragment_entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent::REPLACE,
+                    alpha: wgpu::BlendComponent::REPLACE,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: Default::default(),
+        multisample: Default::default(),
+        depth_stencil: None,
+        multiview: None,
+    })
+}
+
+/// Creates the bind group of the edge detection pass.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the bind group of the edge detection pipeline.
+/// * `edge_detection`: The input which is used to detect edges.
+/// * `edge_source`: The color or depth view in which edges are detected.
+/// * `settings_buffer`: The uniform buffer containing the [SmaaSettings].
+///
+/// Returns:
+///
+/// The new bind group.
+fn create_edge_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    edge_detection: SmaaEdgeDetection,
+    edge_source: &wgpu::TextureView,
+    settings_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: match edge_detection {
+                    SmaaEdgeDetection::Depth => 1,
+                    _ => 0,
+                },
+                resource: wgpu::BindingResource::TextureView(edge_source),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: settings_buffer.as_entire_binding(),
+            },
+        ],
+        layout,
+    })
+}
+
+/// Creates the bind group of the blending weight calculation pass.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the bind group of the blending weight calculation pipeline.
+/// * `edges`: The view containing the detected edges.
+/// * `area_texture`: The view of the precomputed area lookup texture.
+/// * `search_texture`: The view of the precomputed search lookup texture.
+/// * `linear_sampler`: The sampler used for bilinear fetches.
+/// * `point_sampler`: The sampler used for the search lookup texture.
+/// * `settings_buffer`: The uniform buffer containing the [SmaaSettings].
+///
+/// Returns:
+///
+/// The new bind group.
+#[allow(clippy::too_many_arguments)]
+fn create_weight_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    edges: &wgpu::TextureView,
+    area_texture: &wgpu::TextureView,
+    search_texture: &wgpu::TextureView,
+    linear_sampler: &wgpu::Sampler,
+    point_sampler: &wgpu::Sampler,
+    settings_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(edges),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(area_texture),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(search_texture),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::Sampler(linear_sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 4,
+                resource: wgpu::BindingResource::Sampler(point_sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 5,
+                resource: settings_buffer.as_entire_binding(),
+            },
+        ],
+        layout,
+    })
+}
+
+/// Creates the bind group of the neighborhood blending pass.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the bind group of the neighborhood blending pipeline.
+/// * `input_view`: The view which will be anti-aliased.
+/// * `weights`: The view containing the blending weights.
+/// * `linear_sampler`: The sampler used for bilinear fetches.
+///
+/// Returns:
+///
+/// The new bind group.
+fn create_blend_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    input_view: &wgpu::TextureView,
+    weights: &wgpu::TextureView,
+    linear_sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(input_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(weights),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::Sampler(linear_sampler),
+            },
+        ],
+        layout,
+    })
+}
+
+/// Records one of the anti-aliasing passes.
+///
+/// Arguments:
+///
+/// * `encoder`: The command encoder into which the pass is recorded.
+/// * `pipeline`: The pipeline of the pass.
+/// * `bind_group`: The bind group of the pass.
+/// * `output_view`: The view into which the result is written.
+fn render(
+    encoder: &mut wgpu::CommandEncoder,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+    output_view: &wgpu::TextureView,
+) {
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: output_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                store: true,
+            },
+        })],
+        depth_stencil_attachment: None,
+        label: None,
+    });
+    rpass.set_pipeline(pipeline);
+    rpass.set_bind_group(0, bind_group, &[]);
+    rpass.draw(0..3, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shaders_match_bind_group_layouts() {
+        for (edge_detection, entry_point) in [
+            (SmaaEdgeDetection::Luma, "fs_luma"),
+            (SmaaEdgeDetection::Color, "fs_color"),
+            (SmaaEdgeDetection::Depth, "fs_depth"),
+        ] {
+            validation::check_entry_point(
+                "smaa/shader/edge_detection.wgsl",
+                include_str!("shader/edge_detection.wgsl"),
+                naga::ShaderStage::Fragment,
+                entry_point,
+                &[&edge_bind_group_layout_entries(edge_detection)],
+            );
+        }
+        validation::check_entry_point(
+            "smaa/shader/blending_weight.wgsl",
+            include_str!("shader/blending_weight.wgsl"),
+            naga::ShaderStage::Fragment,
+            "fs_main",
+            &[&WEIGHT_BIND_GROUP_LAYOUT_ENTRIES],
+        );
+        validation::check_entry_point(
+            "smaa/shader/neighborhood_blending.wgsl",
+            include_str!("shader/neighborhood_blending.wgsl"),
+            naga::ShaderStage::Fragment,
+            "fs_main",
+            &[&BLEND_BIND_GROUP_LAYOUT_ENTRIES],
+        );
+    }
+}
@@ -0,0 +1,72 @@
+/// The input which is used by the [crate::smaa::SmaaEffect] to detect edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SmaaEdgeDetection {
+    /// Detects edges using the luma of the input.
+    ///
+    /// This is usually the best tradeoff between quality and performance.
+    #[default]
+    Luma,
+    /// Detects edges using the individual color channels of the input.
+    ///
+    /// This also detects edges between colors of similar luma, but is slower.
+    Color,
+    /// Detects edges using a depth texture.
+    ///
+    /// This is the fastest mode, but it only detects geometric edges.
+    /// The depth texture must be provided when the effect is applied,
+    /// otherwise edges are detected using [SmaaEdgeDetection::Luma] instead.
+    Depth,
+}
+
+/// The settings of the [crate::smaa::SmaaEffect].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SmaaSettings {
+    /// The input which is used to detect edges.
+    ///
+    /// Changing the input recreates the edge detection pipeline.
+    pub edge_detection: SmaaEdgeDetection,
+
+    /// The minimum amount of local contrast which is required to detect an edge.
+    ///
+    /// Typical values are in range `0.05..=0.15`, where lower values detect more edges.
+    /// For depth-based edge detection a tenth of this value is used.
+    pub threshold: f32,
+
+    /// The maximum number of steps which are used to search for the end of a
+    /// horizontal or vertical edge.
+    ///
+    /// Each step covers two pixels. The maximum supported value is `112`.
+    pub max_search_steps: u32,
+
+    /// The amount of rounding which is applied to sharp corners in range `0..=1`.
+    ///
+    /// A value of `0` preserves sharp corners, while a value of `1` treats
+    /// them like any other edge.
+    pub corner_rounding: f32,
+}
+
+impl Default for SmaaSettings {
+    fn default() -> Self {
+        Self {
+            edge_detection: SmaaEdgeDetection::default(),
+            threshold: 0.1,
+            max_search_steps: 16,
+            corner_rounding: 0.25,
+        }
+    }
+}
+
+impl SmaaSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 16;
+
+    /// Converts the settings into the layout of the `SmaaSettings` struct in the shaders.
+    pub(super) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.threshold.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.max_search_steps.min(112).to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.corner_rounding.to_le_bytes());
+
+        bytes
+    }
+}
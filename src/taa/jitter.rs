@@ -0,0 +1,126 @@
+/// The number of different sub-pixel offsets before the jitter sequence repeats.
+pub const JITTER_SEQUENCE_LENGTH: u32 = 8;
+
+/// Returns the sub-pixel offset which should be applied to the frame with the given `frame_index`.
+///
+/// The offsets are taken from the Halton(2, 3) sequence, which evenly covers the pixel
+/// after a few frames. The sequence repeats every [JITTER_SEQUENCE_LENGTH] frames.
+///
+/// Arguments:
+///
+/// * `frame_index`: The index of the current frame.
+///
+/// Returns:
+///
+/// The offset in pixels, where both components are in range `-0.5..0.5`.
+pub fn jitter(frame_index: u32) -> [f32; 2] {
+    // The first element of the sequence is skipped, because it is always zero.
+    let index = frame_index % JITTER_SEQUENCE_LENGTH + 1;
+
+    [halton(index, 2) - 0.5, halton(index, 3) - 0.5]
+}
+
+/// Returns the offset which should be added to the projection of the frame
+/// with the given `frame_index`.
+///
+/// The offset is in normalized device coordinates, so that it can be added to the
+/// third column of a perspective projection matrix (`m[2][0]` and `m[2][1]`),
+/// or to the translation of an orthographic projection matrix (`m[3][0]` and `m[3][1]`).
+///
+/// Arguments:
+///
+/// * `frame_index`: The index of the current frame.
+/// * `size`: The size of the frame in pixels.
+///
+/// Returns:
+///
+/// The offset of the projection in normalized device coordinates,
+/// which is zero if the size is empty, e.g. while the window is minimized.
+pub fn projection_jitter(frame_index: u32, size: &wgpu::Extent3d) -> [f32; 2] {
+    if size.width == 0 || size.height == 0 {
+        return [0.0, 0.0];
+    }
+
+    let [x, y] = jitter(frame_index);
+
+    // The y-axis of normalized device coordinates points up, while pixels are counted downwards.
+    [2.0 * x / size.width as f32, -2.0 * y / size.height as f32]
+}
+
+/// Computes an element of the Halton sequence.
+///
+/// Arguments:
+///
+/// * `index`: The index of the element.
+/// * `base`: The base of the sequence.
+///
+/// Returns:
+///
+/// The element in range `0..1`.
+fn halton(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut fraction = 1.0;
+
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn halton_sequence_starts_with_known_values() {
+        let base_2: Vec<f32> = (1..=4).map(|index| halton(index, 2)).collect();
+        assert_eq!(base_2, [0.5, 0.25, 0.75, 0.125]);
+
+        let base_3: Vec<f32> = (1..=4).map(|index| halton(index, 3)).collect();
+        let expected = [1.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0, 4.0 / 9.0];
+        for (value, expected) in base_3.into_iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn jitter_stays_within_the_pixel_and_repeats() {
+        for frame_index in 0..JITTER_SEQUENCE_LENGTH {
+            let offset = jitter(frame_index);
+            assert!(offset.iter().all(|x| (-0.5..0.5).contains(x)));
+            assert_eq!(offset, jitter(frame_index + JITTER_SEQUENCE_LENGTH));
+        }
+
+        // Every offset of the sequence differs from the others.
+        for a in 0..JITTER_SEQUENCE_LENGTH {
+            for b in a + 1..JITTER_SEQUENCE_LENGTH {
+                assert_ne!(jitter(a), jitter(b));
+            }
+        }
+    }
+
+    #[test]
+    fn projection_jitter_is_scaled_to_the_frame() {
+        let size = wgpu::Extent3d {
+            width: 800,
+            height: 600,
+            depth_or_array_layers: 1,
+        };
+        for frame_index in 0..JITTER_SEQUENCE_LENGTH {
+            let [x, y] = projection_jitter(frame_index, &size);
+            let [pixel_x, pixel_y] = jitter(frame_index);
+            assert_eq!(x, 2.0 * pixel_x / 800.0);
+            assert_eq!(y, -2.0 * pixel_y / 600.0);
+        }
+
+        let empty = wgpu::Extent3d {
+            width: 0,
+            height: 600,
+            depth_or_array_layers: 1,
+        };
+        assert_eq!(projection_jitter(1, &empty), [0.0, 0.0]);
+    }
+}
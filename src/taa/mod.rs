@@ -0,0 +1,7 @@
+mod jitter;
+pub use jitter::{jitter, projection_jitter, JITTER_SEQUENCE_LENGTH};
+mod taa_effect;
+pub use taa_effect::{TaaEffect, TaaFrame};
+mod taa_effect_inner;
+mod taa_settings;
+pub use taa_settings::TaaSettings;
@@ -0,0 +1,120 @@
+#include "sampling.wgsl"
+
+struct TaaSettings {
+    blend_factor: f32,
+    reversed_depth: u32,
+};
+
+@group(0) @binding(0) var input_texture : texture_2d<f32>;
+
+@group(1) @binding(0) var history_texture : texture_2d<f32>;
+@group(1) @binding(1) var history_sampler : sampler;
+@group(1) @binding(2) var<uniform> settings : TaaSettings;
+
+@group(2) @binding(0) var velocity_texture : texture_2d<f32>;
+// Bound as a float texture, because loading from depth textures is not supported on every backend.
+@group(2) @binding(1) var depth_texture : texture_2d<f32>;
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) history: vec4<f32>,
+};
+
+fn rgb_to_ycocg(rgb: vec3<f32>) -> vec3<f32> {
+    return vec3<f32>(
+        dot(rgb, vec3<f32>(0.25, 0.5, 0.25)),
+        dot(rgb, vec3<f32>(0.5, 0.0, -0.5)),
+        dot(rgb, vec3<f32>(-0.25, 0.5, -0.25))
+    );
+}
+
+fn ycocg_to_rgb(ycocg: vec3<f32>) -> vec3<f32> {
+    return vec3<f32>(
+        ycocg.x + ycocg.y - ycocg.z,
+        ycocg.x + ycocg.z,
+        ycocg.x - ycocg.y - ycocg.z
+    );
+}
+
+fn load_input(pixel: vec2<i32>, size: vec2<i32>) -> vec4<f32> {
+    return textureLoad(input_texture, clamp(pixel, vec2<i32>(0), size - 1), 0);
+}
+
+// Returns the pixel in the 3x3 neighborhood which is closest to the camera,
+// so that the edges of moving objects use the velocity of the object.
+// The center is kept if no neighbor is closer, e.g. if the empty depth texture is bound.
+fn closest_pixel(pixel: vec2<i32>, size: vec2<i32>) -> vec2<i32> {
+    var closest = pixel;
+    var closest_depth = load_clamped(depth_texture, pixel).r;
+    for (var y = -1; y <= 1; y += 1) {
+        for (var x = -1; x <= 1; x += 1) {
+            let neighbor = clamp(pixel + vec2<i32>(x, y), vec2<i32>(0), size - 1);
+            let depth = load_clamped(depth_texture, neighbor).r;
+            // With a reversed depth buffer, closer pixels have a larger depth.
+            let closer = select(depth < closest_depth, depth > closest_depth, settings.reversed_depth != 0u);
+            if (closer) {
+                closest = neighbor;
+                closest_depth = depth;
+            }
+        }
+    }
+
+    return closest;
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> FragmentOutput {
+    let size = vec2<i32>(textureDimensions(input_texture));
+    let pixel = vec2<i32>(pos.xy);
+    let uv = pos.xy / vec2<f32>(size);
+
+    let current = load_input(pixel, size);
+
+    var out: FragmentOutput;
+    out.color = current;
+    out.history = current;
+
+    // Compute the bounding box of the neighborhood in YCoCg space.
+    var neighborhood_min = vec3<f32>(1e10);
+    var neighborhood_max = vec3<f32>(-1e10);
+    for (var y = -1; y <= 1; y += 1) {
+        for (var x = -1; x <= 1; x += 1) {
+            let neighbor = rgb_to_ycocg(load_input(pixel + vec2<i32>(x, y), size).rgb);
+            neighborhood_min = min(neighborhood_min, neighbor);
+            neighborhood_max = max(neighborhood_max, neighbor);
+        }
+    }
+
+    // Reproject the current pixel into the previous frame.
+    // Without motion vectors the empty velocity texture is bound, which keeps the pixel in place.
+    let velocity = load_clamped(velocity_texture, closest_pixel(pixel, size)).xy;
+    let history_uv = uv - velocity;
+
+    // Discard the history if it was outside of the previous frame.
+    if (any(history_uv < vec2<f32>(0.0)) || any(history_uv > vec2<f32>(1.0))) {
+        return out;
+    }
+
+    let history = textureSampleLevel(history_texture, history_sampler, history_uv, 0.0);
+
+    // Clamp the history to the neighborhood, which removes ghosting.
+    let clamped_history = ycocg_to_rgb(clamp(rgb_to_ycocg(history.rgb), neighborhood_min, neighborhood_max));
+
+    let color = mix(vec4<f32>(clamped_history, history.a), current, settings.blend_factor);
+    out.color = color;
+    out.history = color;
+
+    return out;
+}
+
+// Starts a new history from the current frame, which is used while the history is not valid.
+@fragment
+fn fs_reset(@builtin(position) pos: vec4<f32>) -> FragmentOutput {
+    let size = vec2<i32>(textureDimensions(input_texture));
+    let current = load_input(vec2<i32>(pos.xy), size);
+
+    var out: FragmentOutput;
+    out.color = current;
+    out.history = current;
+    return out;
+}
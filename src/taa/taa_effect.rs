@@ -0,0 +1,104 @@
+use crate::{
+    effect_frame::{validate_depth_texture, FrameInputs},
+    effect_state::{impl_effect, EffectState},
+    EffectFrame, Error,
+};
+
+use super::{taa_effect_inner::TaaEffectInner, TaaSettings};
+
+/// Reduces shimmering and aliasing using temporal anti-aliasing (TAA).
+///
+/// Every frame is blended with a history of the previous frames.
+/// For this to converge, the scene must be rendered with a different
+/// sub-pixel offset each frame, see [crate::taa::projection_jitter].
+///
+/// Without motion vectors the history is sampled at the same position, which only
+/// works well for mostly static scenes. They are passed using [TaaFrame::set_velocity_view]
+/// or [TaaEffect::apply_with_motion].
+pub struct TaaEffect {
+    pub(super) state: EffectState<TaaEffectInner>,
+    pub(super) settings: TaaSettings,
+}
+
+/// The frame into which the scene is rendered before the [TaaEffect] is resolved.
+pub type TaaFrame<'a> = EffectFrame<'a, TaaEffect>;
+
+impl TaaEffect {
+    /// Discards the history, so that the next frame is not blended with the previous ones.
+    ///
+    /// This should be called on camera cuts, where the previous frames are unrelated
+    /// to the current one.
+    pub fn reset_history(&mut self) {
+        if let Some(ref mut inner) = self.state.inner {
+            inner.history_valid = false;
+        }
+    }
+
+    /// Applies the anti-aliasing effect to an arbitrary `input_view`
+    /// using the motion of the scene to reproject the history.
+    ///
+    /// The velocity is expected in texture coordinates, pointing from the position
+    /// in the previous frame to the position in the current frame. If a depth texture
+    /// is given, the velocity of the closest pixel in the 3x3 neighborhood is used,
+    /// which improves the quality at the edges of moving objects.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the effect is recorded.
+    /// * `input_view`: The view which will be anti-aliased.
+    /// * `velocity_view`: The optional view of the motion vectors which belong to `input_view`.
+    /// * `depth_texture`: The optional depth texture which belongs to `input_view`.
+    /// * `output_view`: The view into which the result is written.
+    ///
+    /// Returns:
+    ///
+    /// An [Error] if the depth texture is multisampled or cannot be bound,
+    /// in which case nothing is recorded.
+    pub fn apply_with_motion(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        velocity_view: Option<&wgpu::TextureView>,
+        depth_texture: Option<&wgpu::Texture>,
+        output_view: &wgpu::TextureView,
+    ) -> Result<(), Error> {
+        if let Some(depth_texture) = depth_texture {
+            validate_depth_texture(depth_texture)?;
+        }
+
+        let inputs = FrameInputs {
+            depth_texture,
+            velocity_view,
+            ..Default::default()
+        };
+        self.state
+            .apply(device, encoder, input_view, &inputs, output_view);
+
+        Ok(())
+    }
+
+    /// Creates the resources of the effect using the current size, format and settings.
+    fn create_inner(&self, device: &wgpu::Device) -> TaaEffectInner {
+        TaaEffectInner::new(
+            device,
+            &self.state.allocation_size(),
+            self.state.format(),
+            &self.settings,
+        )
+    }
+}
+
+impl_effect!(TaaEffect, "anti-aliasing", TaaSettings, depth);
+
+impl<'a> TaaFrame<'a> {
+    /// Sets the motion vectors of the scene, which are used to reproject the history.
+    ///
+    /// Arguments:
+    ///
+    /// * `velocity_view`: The view of the velocity texture which was written while rendering into this frame.
+    pub fn set_velocity_view(&mut self, velocity_view: &'a wgpu::TextureView) {
+        self.inputs.velocity_view = Some(velocity_view);
+    }
+}
@@ -0,0 +1,438 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    render_target::create_texture_view,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::TaaSettings;
+
+/// The format of the history textures, which keeps the precision of HDR inputs.
+const HISTORY_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// The entries of the layout of the input bind group, which matches group 0 of `shader/taa.wgsl`.
+const INPUT_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 1] = [texture_layout_entry(
+    0,
+    wgpu::TextureSampleType::Float { filterable: false },
+)];
+
+/// The entries of the layout of the history bind group, which matches group 1 of `shader/taa.wgsl`.
+const HISTORY_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 3] = [
+    texture_layout_entry(0, wgpu::TextureSampleType::Float { filterable: true }),
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 2,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(TaaSettings::SIZE as u64),
+        },
+        count: None,
+    },
+];
+
+/// The entries of the layout of the motion bind group, which matches group 2 of `shader/taa.wgsl`.
+const MOTION_BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 2] = [
+    texture_layout_entry(0, wgpu::TextureSampleType::Float { filterable: false }),
+    texture_layout_entry(1, wgpu::TextureSampleType::Float { filterable: false }),
+];
+
+/// Wraps the internal fields and implementation
+/// of a [crate::taa::TaaEffect].
+pub(super) struct TaaEffectInner {
+    pub(super) pipeline: wgpu::RenderPipeline,
+    pub(super) reset_pipeline: wgpu::RenderPipeline,
+    pub(super) _vertex_shader: VertexShader,
+    pub(super) input_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) history_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) motion_bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) sampler: wgpu::Sampler,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) bind_groups: BindGroupCache,
+    pub(super) motion_bind_groups: BindGroupCache<[wgpu::Id; 2]>,
+    pub(super) histories: [wgpu::TextureView; 2],
+    pub(super) history_bind_groups: [wgpu::BindGroup; 2],
+    pub(super) current_history: usize,
+    pub(super) history_valid: bool,
+    pub(super) dummy_velocity: wgpu::TextureView,
+    pub(super) dummy_depth: wgpu::TextureView,
+}
+
+impl TaaEffectInner {
+    /// Creates a new [TaaEffectInner].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The size of the frame which will later be processed.
+    /// * `format`: The texture format of the post-processed frame.
+    /// * `settings`: The settings of the anti-aliasing.
+    ///
+    /// Returns:
+    ///
+    /// The new [TaaEffectInner].
+    pub(super) fn new(
+        device: &wgpu::Device,
+        size: &wgpu::Extent3d,
+        format: EffectFormat,
+        settings: &TaaSettings,
+    ) -> Self {
+        let histories = [
+            create_texture_view(device, size, HISTORY_FORMAT),
+            create_texture_view(device, size, HISTORY_FORMAT),
+        ];
+
+        let one_pixel = wgpu::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let dummy_velocity =
+            create_texture_view(device, &one_pixel, wgpu::TextureFormat::Rg16Float);
+        let dummy_depth =
+            create_texture_view(device, &one_pixel, wgpu::TextureFormat::Depth32Float);
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &settings.to_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let input_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &INPUT_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let history_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &HISTORY_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let motion_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &MOTION_BIND_GROUP_LAYOUT_ENTRIES,
+            });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[
+                &input_bind_group_layout,
+                &history_bind_group_layout,
+                &motion_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let taa_shader = include_composed_wgsl!(device, "shader/taa.wgsl");
+
+        let replace = Some(wgpu::BlendState {
+            color: wgpu::BlendComponent::REPLACE,
+            alpha: wgpu::BlendComponent::REPLACE,
+        });
+        let targets = [
+            Some(wgpu::ColorTargetState {
+                format: format.output,
+                blend: replace,
+                write_mask: wgpu::ColorWrites::ALL,
+            }),
+            Some(wgpu::ColorTargetState {
+                format: HISTORY_FORMAT,
+                blend: replace,
+                write_mask: wgpu::ColorWrites::ALL,
+            }),
+        ];
+
+        // Both pipelines write the output and the next history,
+        // but the reset pipeline ignores the previous history.
+        let create_pipeline = |entry_point: &str| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: None,
+                layout: Some(&pipeline_layout),
+                vertex: vertex_shader.state(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &taa_shader,
+                    entry_point,
+                    targets: &targets,
+                }),
+                primitive: Default::default(),
+                multisample: Default::default(),
+                depth_stencil: None,
+                multiview: None,
+            })
+        };
+        let pipeline = create_pipeline("fs_main");
+        let reset_pipeline = create_pipeline("fs_reset");
+
+        let history_bind_groups = create_history_bind_groups(
+            device,
+            &history_bind_group_layout,
+            &histories,
+            &sampler,
+            &settings_buffer,
+        );
+
+        Self {
+            pipeline,
+            reset_pipeline,
+            _vertex_shader: vertex_shader,
+            input_bind_group_layout,
+            history_bind_group_layout,
+            motion_bind_group_layout,
+            sampler,
+            settings_buffer,
+            bind_groups: BindGroupCache::new(),
+            motion_bind_groups: BindGroupCache::new(),
+            histories,
+            history_bind_groups,
+            current_history: 0,
+            history_valid: false,
+            dummy_velocity,
+            dummy_depth,
+        }
+    }
+
+    /// Uploads new settings into the uniform buffer.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device, which is not needed to upload the settings.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the anti-aliasing.
+    pub(super) fn set_settings(
+        &self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &TaaSettings,
+    ) {
+        queue.write_buffer(&self.settings_buffer, 0, &settings.to_bytes());
+    }
+}
+
+impl EffectInner for TaaEffectInner {
+    /// Resizes the [TaaEffectInner] after creation.
+    ///
+    /// The history is discarded, because it does not match the new size anymore.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `size`: The new size to which the effect should be resized.
+    fn resize(&mut self, device: &wgpu::Device, size: &wgpu::Extent3d) {
+        self.histories = [
+            create_texture_view(device, size, HISTORY_FORMAT),
+            create_texture_view(device, size, HISTORY_FORMAT),
+        ];
+        self.history_bind_groups = create_history_bind_groups(
+            device,
+            &self.history_bind_group_layout,
+            &self.histories,
+            &self.sampler,
+            &self.settings_buffer,
+        );
+        self.bind_groups.clear();
+        self.motion_bind_groups.clear();
+        self.history_valid = false;
+    }
+
+    /// Records the temporal anti-aliasing pass which blends `input_view`
+    /// with the history and writes the result into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the pass is recorded.
+    /// * `input_view`: The view of the current frame.
+    /// * `inputs`: The additional inputs, which contain the optional
+    ///   motion vectors and depth of the current frame.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let FrameInputs {
+            depth_view,
+            velocity_view,
+            ..
+        } = *inputs;
+
+        let input_bind_group = self.bind_groups.get_or_insert_with(input_view, || {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(input_view),
+                }],
+                layout: &self.input_bind_group_layout,
+            })
+        });
+
+        let velocity_view = velocity_view.unwrap_or(&self.dummy_velocity);
+        let depth_view = depth_view.unwrap_or(&self.dummy_depth);
+        let motion_bind_group = self.motion_bind_groups.get_or_insert_with_key(
+            [velocity_view.global_id(), depth_view.global_id()],
+            || {
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(velocity_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(depth_view),
+                        },
+                    ],
+                    layout: &self.motion_bind_group_layout,
+                })
+            },
+        );
+
+        // The previous frame is read from the current history,
+        // while the new frame is written into the other one.
+        let next_history = 1 - self.current_history;
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[
+                    Some(wgpu::RenderPassColorAttachment {
+                        view: output_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: true,
+                        },
+                    }),
+                    Some(wgpu::RenderPassColorAttachment {
+                        view: &self.histories[next_history],
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: true,
+                        },
+                    }),
+                ],
+                depth_stencil_attachment: None,
+                label: None,
+            });
+            // Until the history contains a previous frame, it is started from the current frame.
+            rpass.set_pipeline(if self.history_valid {
+                &self.pipeline
+            } else {
+                &self.reset_pipeline
+            });
+            rpass.set_bind_group(0, input_bind_group, &[]);
+            rpass.set_bind_group(1, &self.history_bind_groups[self.current_history], &[]);
+            rpass.set_bind_group(2, motion_bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+
+        self.current_history = next_history;
+        self.history_valid = true;
+    }
+}
+
+/// Creates the layout entry of a 2D texture with the given `sample_type`.
+const fn texture_layout_entry(
+    binding: u32,
+    sample_type: wgpu::TextureSampleType,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+/// Creates the bind groups which bind each of the `histories` as the previous frame.
+///
+/// Arguments:
+///
+/// * `device`: The current graphics device.
+/// * `layout`: The layout of the history bind group.
+/// * `histories`: The views of both history textures.
+/// * `sampler`: The linear sampler used to sample the history.
+/// * `settings_buffer`: The uniform buffer containing the [TaaSettings].
+///
+/// Returns:
+///
+/// One bind group for each history texture.
+fn create_history_bind_groups(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    histories: &[wgpu::TextureView; 2],
+    sampler: &wgpu::Sampler,
+    settings_buffer: &wgpu::Buffer,
+) -> [wgpu::BindGroup; 2] {
+    histories.each_ref().map(|history| {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(history),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: settings_buffer.as_entire_binding(),
+                },
+            ],
+            layout,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shader_matches_bind_group_layouts() {
+        for entry_point in ["fs_main", "fs_reset"] {
+            validation::check_entry_point(
+                "taa/shader/taa.wgsl",
+                include_str!("shader/taa.wgsl"),
+                naga::ShaderStage::Fragment,
+                entry_point,
+                &[
+                    &INPUT_BIND_GROUP_LAYOUT_ENTRIES,
+                    &HISTORY_BIND_GROUP_LAYOUT_ENTRIES,
+                    &MOTION_BIND_GROUP_LAYOUT_ENTRIES,
+                ],
+            );
+        }
+    }
+}
@@ -0,0 +1,37 @@
+/// The settings of the [crate::taa::TaaEffect].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TaaSettings {
+    /// The weight of the current frame when it is blended with the history in range `0..=1`.
+    ///
+    /// Lower values result in a smoother image, but adapt slower to changes.
+    pub blend_factor: f32,
+
+    /// Whether the depth buffer is reversed, i.e. the near plane is at a depth of `1`.
+    ///
+    /// The depth is used to find the pixel closest to the camera, whose velocity is used
+    /// at the edges of moving objects. For a reversed depth buffer this must be `true`.
+    pub reversed_depth: bool,
+}
+
+impl Default for TaaSettings {
+    fn default() -> Self {
+        Self {
+            blend_factor: 0.1,
+            reversed_depth: false,
+        }
+    }
+}
+
+impl TaaSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 16;
+
+    /// Converts the settings into the layout of the `TaaSettings` struct in `taa.wgsl`.
+    pub(super) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.blend_factor.to_le_bytes());
+        bytes[4..8].copy_from_slice(&u32::from(self.reversed_depth).to_le_bytes());
+
+        bytes
+    }
+}
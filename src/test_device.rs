@@ -0,0 +1,42 @@
+/// The environment variable which allows skipping the tests which need a graphics adapter.
+const SKIP_GPU_TESTS_VAR: &str = "WPP_SKIP_GPU_TESTS";
+
+/// Requests a device for running effects in unit tests.
+///
+/// This is the same adapter lookup as in `tests/common`, which cannot be shared
+/// with the unit tests: A software adapter is preferred, so that the results are
+/// reproducible, but any other adapter is used if none is available.
+///
+/// Returns:
+///
+/// The device and queue or `None` if no adapter is available at all and
+/// skipping the tests was requested using the `WPP_SKIP_GPU_TESTS` environment variable.
+///
+/// Panics:
+///
+/// If no adapter is available and skipping the tests was not requested,
+/// or if the adapter does not provide a device.
+pub(crate) fn device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    pollster::block_on(async {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let fallback = wgpu::RequestAdapterOptions {
+            force_fallback_adapter: true,
+            ..Default::default()
+        };
+        let adapter = match instance.request_adapter(&fallback).await {
+            Some(adapter) => Some(adapter),
+            None => instance.request_adapter(&Default::default()).await,
+        };
+        let Some(adapter) = adapter else {
+            assert!(
+                std::env::var_os(SKIP_GPU_TESTS_VAR).is_some(),
+                "No graphics adapter available, set {SKIP_GPU_TESTS_VAR}=1 to skip the tests which need one."
+            );
+            eprintln!("No graphics adapter available, skipping.");
+            return None;
+        };
+
+        let device = adapter.request_device(&wgpu::DeviceDescriptor::default(), None);
+        Some(device.await.expect("Failed to request a device"))
+    })
+}
@@ -0,0 +1,5 @@
+mod tonemap_effect;
+pub use tonemap_effect::{TonemapEffect, TonemapFrame};
+mod tonemap_effect_inner;
+mod tonemap_settings;
+pub use tonemap_settings::{TonemapOperator, TonemapSettings};
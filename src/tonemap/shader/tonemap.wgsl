@@ -0,0 +1,149 @@
+#include "color.wgsl"
+
+struct TonemapSettings {
+    tonemapper: u32,
+    exposure: f32,
+    white_point: f32,
+    encode_srgb: u32,
+};
+
+@group(0) @binding(0) var input_texture : texture_2d<f32>;
+@group(0) @binding(1) var<uniform> settings : TonemapSettings;
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (1.0 + color);
+}
+
+// Reinhard applied to the luminance, which maps the white point to 1.
+// See: https://64.github.io/tonemapping/#extended-reinhard-luminance-tone-map.
+fn reinhard_extended(color: vec3<f32>) -> vec3<f32> {
+    let luminance_old = luma_rec709(color);
+    if (luminance_old <= 0.0) {
+        return vec3<f32>(0.0);
+    }
+
+    let white_squared = settings.white_point * settings.white_point;
+    let luminance_new = luminance_old * (1.0 + luminance_old / white_squared) / (1.0 + luminance_old);
+    return color * (luminance_new / luminance_old);
+}
+
+// The fit of the ACES reference rendering transform and output device transform by Stephen Hill.
+// See: https://github.com/TheRealMJP/BakingLab/blob/master/BakingLab/ACES.hlsl.
+fn aces_fitted(color: vec3<f32>) -> vec3<f32> {
+    // sRGB => XYZ => D65_2_D60 => AP1 => RRT_SAT
+    let input_matrix = mat3x3<f32>(
+        vec3<f32>(0.59719, 0.07600, 0.02840),
+        vec3<f32>(0.35458, 0.90834, 0.13383),
+        vec3<f32>(0.04823, 0.01566, 0.83777)
+    );
+    // ODT_SAT => XYZ => D60_2_D65 => sRGB
+    let output_matrix = mat3x3<f32>(
+        vec3<f32>(1.60475, -0.10208, -0.00327),
+        vec3<f32>(-0.53108, 1.10813, -0.07276),
+        vec3<f32>(-0.07367, -0.00605, 1.07602)
+    );
+
+    let v = input_matrix * color;
+    let a = v * (v + 0.0245786) - 0.000090537;
+    let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+    return output_matrix * (a / b);
+}
+
+// A minimal version of AgX by Troy Sobotka, using the polynomial approximation by Benjamin Wrensch.
+// See: https://iolite-engine.com/blog_posts/minimal_agx_implementation.
+fn agx_contrast(x: vec3<f32>) -> vec3<f32> {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+
+    return 15.5 * x4 * x2
+        - 40.14 * x4 * x
+        + 31.96 * x4
+        - 6.868 * x2 * x
+        + 0.4298 * x2
+        + 0.1191 * x
+        - 0.00232;
+}
+
+fn agx(color: vec3<f32>) -> vec3<f32> {
+    let inset_matrix = mat3x3<f32>(
+        vec3<f32>(0.842479062253094, 0.0423282422610123, 0.0423756549057051),
+        vec3<f32>(0.0784335999999992, 0.878468636469772, 0.0784336),
+        vec3<f32>(0.0792237451477643, 0.0791661274605434, 0.879142973793104)
+    );
+    let outset_matrix = mat3x3<f32>(
+        vec3<f32>(1.19687900512017, -0.0528968517574562, -0.0529716355144438),
+        vec3<f32>(-0.0980208811401368, 1.15190312990417, -0.0980434501171241),
+        vec3<f32>(-0.0990297440797205, -0.0989611768448433, 1.15107367264116)
+    );
+    let min_ev = -12.47393;
+    let max_ev = 4.026069;
+
+    var v = inset_matrix * max(color, vec3<f32>(0.0));
+    v = clamp(log2(max(v, vec3<f32>(1e-10))), vec3<f32>(min_ev), vec3<f32>(max_ev));
+    v = (v - min_ev) / (max_ev - min_ev);
+    v = agx_contrast(v);
+    v = outset_matrix * v;
+
+    // The curve produces display encoded values, which are converted back to linear.
+    return pow(max(v, vec3<f32>(0.0)), vec3<f32>(2.2));
+}
+
+// The Khronos PBR Neutral tone mapper, which keeps base colors as close as possible.
+// See: https://github.com/KhronosGroup/ToneMapping/tree/main/PBR_Neutral.
+fn pbr_neutral(color: vec3<f32>) -> vec3<f32> {
+    let start_compression = 0.8 - 0.04;
+    let desaturation = 0.15;
+
+    let x = min(color.r, min(color.g, color.b));
+    var offset = 0.04;
+    if (x < 0.08) {
+        offset = x - 6.25 * x * x;
+    }
+    var v = color - offset;
+
+    let peak = max(v.r, max(v.g, v.b));
+    if (peak < start_compression) {
+        return v;
+    }
+
+    let d = 1.0 - start_compression;
+    let new_peak = 1.0 - d * d / (peak + d - start_compression);
+    v = v * (new_peak / peak);
+
+    let g = 1.0 - 1.0 / (desaturation * (peak - new_peak) + 1.0);
+    return mix(v, vec3<f32>(new_peak), g);
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let input = textureLoad(input_texture, vec2<i32>(pos.xy), 0);
+    let exposed = max(input.rgb * exp2(settings.exposure), vec3<f32>(0.0));
+
+    var color: vec3<f32>;
+    switch (settings.tonemapper) {
+        case 0u: {
+            color = reinhard(exposed);
+        }
+        case 1u: {
+            color = reinhard_extended(exposed);
+        }
+        case 2u: {
+            color = aces_fitted(exposed);
+        }
+        case 3u: {
+            color = agx(exposed);
+        }
+        default: {
+            color = pbr_neutral(exposed);
+        }
+    }
+    color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // Formats without the `Srgb` suffix store the values as they are,
+    // so the encoding must be applied manually.
+    if (settings.encode_srgb != 0u) {
+        color = encode_srgb(color);
+    }
+
+    return vec4<f32>(color, input.a);
+}
@@ -0,0 +1,29 @@
+use crate::{
+    effect_state::{impl_effect, EffectState},
+    EffectFrame,
+};
+
+use super::{tonemap_effect_inner::TonemapEffectInner, TonemapSettings};
+
+/// Maps an HDR frame into the displayable range of the output format.
+///
+/// The frames of this effect are `Rgba16Float` by default, independent of the
+/// format of the output, another format can be chosen using an [crate::EffectFormat].
+/// If the output is a unorm format without the `Srgb` suffix, the result is
+/// encoded using the sRGB transfer function. Float outputs receive linear colors.
+pub struct TonemapEffect {
+    pub(super) state: EffectState<TonemapEffectInner>,
+    pub(super) settings: TonemapSettings,
+}
+
+/// The frame into which the scene is rendered before the [TonemapEffect] is resolved.
+pub type TonemapFrame<'a> = EffectFrame<'a, TonemapEffect>;
+
+impl TonemapEffect {
+    /// Creates the resources of the effect using the current format and settings.
+    fn create_inner(&self, device: &wgpu::Device) -> TonemapEffectInner {
+        TonemapEffectInner::new(device, self.state.format(), &self.settings)
+    }
+}
+
+impl_effect!(TonemapEffect, "tonemapping", TonemapSettings, hdr);
@@ -0,0 +1,225 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bind_group_cache::BindGroupCache,
+    effect_format::is_linear,
+    effect_frame::FrameInputs,
+    effect_state::EffectInner,
+    shader::{include_composed_wgsl, VertexShader},
+    EffectFormat,
+};
+
+use super::TonemapSettings;
+
+/// The entries of the bind group layout, which match the bindings of `shader/tonemap.wgsl`.
+const BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 2] = [
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: wgpu::BufferSize::new(TonemapSettings::SIZE as u64),
+        },
+        count: None,
+    },
+];
+
+/// Wraps the internal fields and implementation
+/// of a [crate::tonemap::TonemapEffect].
+pub(super) struct TonemapEffectInner {
+    pub(super) pipeline: wgpu::RenderPipeline,
+    pub(super) _vertex_shader: VertexShader,
+    pub(super) bind_group_layout: wgpu::BindGroupLayout,
+    pub(super) settings_buffer: wgpu::Buffer,
+    pub(super) encode_srgb: bool,
+    pub(super) bind_groups: BindGroupCache,
+}
+
+impl TonemapEffectInner {
+    /// Creates a new [TonemapEffectInner].
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `format`: The texture format of the display, into which the frame is resolved.
+    /// * `settings`: The settings of the tonemapping.
+    ///
+    /// Returns:
+    ///
+    /// The new [TonemapEffectInner].
+    pub(super) fn new(
+        device: &wgpu::Device,
+        format: EffectFormat,
+        settings: &TonemapSettings,
+    ) -> Self {
+        // Only unorm outputs store encoded colors, float and sRGB views expect linear colors.
+        let encode_srgb = !is_linear(format.output);
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &settings.to_bytes(encode_srgb),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &BIND_GROUP_LAYOUT_ENTRIES,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader = VertexShader::fullscreen(device);
+        let tonemap_shader = include_composed_wgsl!(device, "shader/tonemap.wgsl");
+
+        let tonemap_shader_vert = vertex_shader.state();
+
+        let tonemap_shader_frag = wgpu::FragmentState {
+            module: &tonemap_shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: format.output,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent::REPLACE,
+                    alpha: wgpu::BlendComponent::REPLACE,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        };
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: tonemap_shader_vert,
+            fragment: Some(tonemap_shader_frag),
+            primitive: Default::default(),
+            multisample: Default::default(),
+            depth_stencil: None,
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            _vertex_shader: vertex_shader,
+            bind_group_layout,
+            settings_buffer,
+            encode_srgb,
+            bind_groups: BindGroupCache::new(),
+        }
+    }
+
+    /// Uploads new settings into the uniform buffer.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device, which is not needed to upload the settings.
+    /// * `queue`: The queue which is used to upload the settings.
+    /// * `settings`: The new settings of the tonemapping.
+    pub(super) fn set_settings(
+        &self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        settings: &TonemapSettings,
+    ) {
+        queue.write_buffer(
+            &self.settings_buffer,
+            0,
+            &settings.to_bytes(self.encode_srgb),
+        );
+    }
+}
+
+impl EffectInner for TonemapEffectInner {
+    /// Releases the bind groups of the previous frame targets, because
+    /// the tonemapping pass itself does not depend on the size of the frame.
+    ///
+    /// Arguments:
+    ///
+    /// * `_device`: The current graphics device.
+    /// * `_size`: The new size of the frame.
+    fn resize(&mut self, _device: &wgpu::Device, _size: &wgpu::Extent3d) {
+        self.bind_groups.clear();
+    }
+
+    /// Records the tonemapping pass which reads from `input_view`
+    /// and writes into `output_view`.
+    ///
+    /// Arguments:
+    ///
+    /// * `device`: The current graphics device.
+    /// * `encoder`: The command encoder into which the pass is recorded.
+    /// * `input_view`: The HDR view which will be mapped.
+    /// * `_inputs`: The additional inputs, which are not used by the tonemapping pass.
+    /// * `output_view`: The view into which the result is written.
+    fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input_view: &wgpu::TextureView,
+        _inputs: &FrameInputs<'_>,
+        output_view: &wgpu::TextureView,
+    ) {
+        let bind_group = self.bind_groups.get_or_insert_with(input_view, || {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(input_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: self.settings_buffer.as_entire_binding(),
+                    },
+                ],
+                layout: &self.bind_group_layout,
+            })
+        });
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+            label: None,
+        });
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::validation;
+
+    #[test]
+    fn shader_matches_bind_group_layout() {
+        validation::check_entry_point(
+            "tonemap/shader/tonemap.wgsl",
+            include_str!("shader/tonemap.wgsl"),
+            naga::ShaderStage::Fragment,
+            "fs_main",
+            &[&BIND_GROUP_LAYOUT_ENTRIES],
+        );
+    }
+}
@@ -0,0 +1,72 @@
+/// The operators which can be used by the [crate::tonemap::TonemapEffect].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TonemapOperator {
+    /// The simple Reinhard operator `x / (1 + x)`, applied to every channel.
+    Reinhard,
+    /// The Reinhard operator applied to the luminance,
+    /// which maps [TonemapSettings::white_point] to white.
+    ReinhardExtended,
+    /// The fit of the ACES filmic curve by Stephen Hill.
+    AcesFitted,
+    /// A minimal version of AgX, which desaturates very bright colors gracefully.
+    #[default]
+    AgX,
+    /// The Khronos PBR Neutral operator, which keeps base colors as close as possible.
+    PbrNeutral,
+}
+
+impl TonemapOperator {
+    /// Returns the index which identifies the operator in `tonemap.wgsl`.
+    fn index(self) -> u32 {
+        match self {
+            TonemapOperator::Reinhard => 0,
+            TonemapOperator::ReinhardExtended => 1,
+            TonemapOperator::AcesFitted => 2,
+            TonemapOperator::AgX => 3,
+            TonemapOperator::PbrNeutral => 4,
+        }
+    }
+}
+
+/// The settings of the [crate::tonemap::TonemapEffect].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TonemapSettings {
+    /// The operator which maps the HDR colors into the displayable range.
+    pub operator: TonemapOperator,
+
+    /// The exposure in stops, the input is multiplied by `2^exposure` before it is mapped.
+    pub exposure: f32,
+
+    /// The luminance which is mapped to white by [TonemapOperator::ReinhardExtended].
+    pub white_point: f32,
+}
+
+impl Default for TonemapSettings {
+    fn default() -> Self {
+        Self {
+            operator: TonemapOperator::default(),
+            exposure: 0.0,
+            white_point: 4.0,
+        }
+    }
+}
+
+impl TonemapSettings {
+    /// The size of the settings when uploaded to a uniform buffer.
+    pub(super) const SIZE: usize = 16;
+
+    /// Converts the settings into the layout of the `TonemapSettings` struct in `tonemap.wgsl`.
+    ///
+    /// Arguments:
+    ///
+    /// * `encode_srgb`: Whether the output must be encoded using the sRGB transfer function.
+    pub(super) fn to_bytes(self, encode_srgb: bool) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.operator.index().to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.exposure.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.white_point.to_le_bytes());
+        bytes[12..16].copy_from_slice(&u32::from(encode_srgb).to_le_bytes());
+
+        bytes
+    }
+}
@@ -0,0 +1,81 @@
+//! Helpers which are shared by the integration tests.
+//!
+//! The unit tests of the crate use the same adapter lookup in `src/test_device.rs`.
+
+/// The environment variable which allows skipping the tests which need a graphics adapter.
+pub const SKIP_GPU_TESTS_VAR: &str = "WPP_SKIP_GPU_TESTS";
+
+/// Requests an adapter for running effects in tests.
+///
+/// A software adapter is preferred, so that the results are reproducible
+/// across machines, but any other adapter is used if none is available.
+///
+/// Returns:
+///
+/// The adapter or `None` if no adapter is available at all and
+/// the test should be skipped (see [skip_without_adapter]).
+///
+/// Panics:
+///
+/// If no adapter is available and skipping the tests was not requested.
+#[allow(dead_code)]
+pub fn adapter() -> Option<wgpu::Adapter> {
+    let adapter = pollster::block_on(async {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        match instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                force_fallback_adapter: true,
+                ..Default::default()
+            })
+            .await
+        {
+            Some(adapter) => Some(adapter),
+            None => instance.request_adapter(&Default::default()).await,
+        }
+    });
+    if adapter.is_none() {
+        skip_without_adapter();
+    }
+    adapter
+}
+
+/// Requests a device of `adapter` using the default features and limits.
+#[allow(dead_code)]
+pub fn request_device(adapter: &wgpu::Adapter) -> Option<(wgpu::Device, wgpu::Queue)> {
+    pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None)).ok()
+}
+
+/// Requests a device for running effects in tests, see [adapter].
+///
+/// Returns:
+///
+/// The device and queue or `None` if no adapter is available at all and
+/// the test should be skipped (see [skip_without_adapter]).
+///
+/// Panics:
+///
+/// If no adapter is available and skipping the tests was not requested,
+/// or if the adapter does not provide a device.
+#[allow(dead_code)]
+pub fn device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let adapter = adapter()?;
+    Some(request_device(&adapter).expect("Failed to request a device"))
+}
+
+/// Reports that a test is skipped because no graphics adapter is available.
+///
+/// This is called by [adapter] and [device], so tests only need to return if they return `None`.
+///
+/// Tests which need an adapter must not pass silently in environments without one,
+/// so skipping them has to be requested by setting the [SKIP_GPU_TESTS_VAR] environment variable.
+///
+/// Panics:
+///
+/// If the [SKIP_GPU_TESTS_VAR] environment variable is not set.
+pub fn skip_without_adapter() {
+    assert!(
+        std::env::var_os(SKIP_GPU_TESTS_VAR).is_some(),
+        "No graphics adapter available, set {SKIP_GPU_TESTS_VAR}=1 to skip the tests which need one."
+    );
+    eprintln!("No graphics adapter available, skipping.");
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "grayscale")]
+
+mod common;
+
+use wpp::{
+    grayscale::{GrayscaleEffect, GrayscaleSettings},
+    EffectFormat, Error,
+};
+
+#[test]
+fn invalid_sizes_and_formats_are_reported() {
+    let Some(adapter) = common::adapter() else {
+        return;
+    };
+    let (device, _) = common::request_device(&adapter).unwrap();
+
+    let size = wgpu::Extent3d {
+        width: 16,
+        height: 16,
+        depth_or_array_layers: 1,
+    };
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+
+    let too_large = wgpu::Extent3d {
+        width: device.limits().max_texture_dimension_2d + 1,
+        ..size
+    };
+    assert!(matches!(
+        GrayscaleEffect::try_new(&adapter, &device, &too_large, format),
+        Err(Error::InvalidSize { .. })
+    ));
+
+    let unfilterable = EffectFormat::new(wgpu::TextureFormat::Rgba32Float, format);
+    assert!(matches!(
+        GrayscaleEffect::try_new(&adapter, &device, &size, unfilterable),
+        Err(Error::NotFilterable(wgpu::TextureFormat::Rgba32Float))
+    ));
+
+    let compressed = wgpu::TextureFormat::Bc1RgbaUnorm;
+    assert!(matches!(
+        GrayscaleEffect::try_new(&adapter, &device, &size, compressed),
+        Err(Error::MissingFeatures { .. })
+    ));
+
+    assert!(matches!(
+        GrayscaleEffect::try_with_sample_count(&adapter, &device, &size, format, 3),
+        Err(Error::UnsupportedSampleCount {
+            sample_count: 3,
+            ..
+        })
+    ));
+
+    let settings = GrayscaleSettings {
+        strength: 0.5,
+        ..Default::default()
+    };
+    let effect = GrayscaleEffect::try_with_settings_and_sample_count(
+        &adapter, &device, &size, format, &settings, 4,
+    )
+    .unwrap();
+    assert_eq!(effect.sample_count(), 4);
+    assert_eq!(*effect.settings(), settings);
+
+    let mut effect = GrayscaleEffect::try_new(&adapter, &device, &size, format).unwrap();
+    assert!(matches!(
+        effect.try_resize(&device, &too_large),
+        Err(Error::InvalidSize { .. })
+    ));
+    effect.try_resize(&device, &size).unwrap();
+
+    // Empty sizes are valid and suspend the effect.
+    let zero = wgpu::Extent3d { width: 0, ..size };
+    effect.try_resize(&device, &zero).unwrap();
+    assert!(effect.is_suspended());
+    let effect = GrayscaleEffect::try_new(&adapter, &device, &zero, format).unwrap();
+    assert!(effect.is_suspended());
+}
+
+#[test]
+fn adapter_specific_formats_are_accepted() {
+    let Some(adapter) = common::adapter() else {
+        return;
+    };
+    let features = wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+    if !adapter.features().contains(features) {
+        eprintln!("Adapter specific format features are not supported, skipping.");
+        return;
+    }
+    let descriptor = wgpu::DeviceDescriptor {
+        features,
+        ..Default::default()
+    };
+    let (device, _) = pollster::block_on(adapter.request_device(&descriptor, None)).unwrap();
+
+    let size = wgpu::Extent3d {
+        width: 16,
+        height: 16,
+        depth_or_array_layers: 1,
+    };
+    let intermediate = wgpu::TextureFormat::Rgba32Float;
+    let format = EffectFormat::new(intermediate, wgpu::TextureFormat::Rgba8Unorm);
+    let supported = adapter.get_texture_format_features(intermediate);
+
+    // The effect must either be created or fail with an error, but never panic.
+    let result = GrayscaleEffect::try_new(&adapter, &device, &size, format);
+    if supported
+        .flags
+        .contains(wgpu::TextureFormatFeatureFlags::FILTERABLE)
+    {
+        assert!(result.is_ok());
+    } else {
+        assert!(matches!(result, Err(Error::NotFilterable(_))));
+    }
+}
+
+#[cfg(feature = "dof")]
+#[test]
+fn unreadable_depth_is_reported() {
+    use wpp::dof::DofEffect;
+
+    let Some((device, _)) = common::device() else {
+        return;
+    };
+
+    let size = wgpu::Extent3d {
+        width: 16,
+        height: 16,
+        depth_or_array_layers: 1,
+    };
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let depth_format = wgpu::TextureFormat::Depth32Float;
+
+    let mut effect = DofEffect::with_sample_count(&device, &size, format, 4);
+    assert!(matches!(
+        effect.set_depth_format(&device, Some(depth_format)),
+        Err(Error::MultisampledDepth { sample_count: 4 })
+    ));
+    effect.set_depth_format(&device, None).unwrap();
+
+    let create_texture = |format, usage| {
+        device.create_texture(&wgpu::TextureDescriptor {
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            label: None,
+            view_formats: &[],
+        })
+    };
+    let view = |texture: &wgpu::Texture| texture.create_view(&Default::default());
+    let input = create_texture(
+        format,
+        wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+    );
+    let output = create_texture(format, wgpu::TextureUsages::RENDER_ATTACHMENT);
+    let depth = create_texture(depth_format, wgpu::TextureUsages::RENDER_ATTACHMENT);
+
+    let mut effect = DofEffect::new(&device, &size, format);
+    let mut encoder = device.create_command_encoder(&Default::default());
+    assert!(matches!(
+        effect.apply_with_depth(&device, &mut encoder, &view(&input), &depth, &view(&output)),
+        Err(Error::DepthNotBindable)
+    ));
+}
@@ -0,0 +1,301 @@
+//! Renders deterministic images through the effects and compares the results
+//! with the reference images in `tests/golden`.
+//!
+//! Run the tests with `WPP_UPDATE_GOLDEN=1` to write new reference images after an
+//! intended change. When a comparison fails, the actual result and a diff image,
+//! which marks every pixel outside the tolerance in red, are written to the
+//! temporary directory of the test target.
+
+mod common;
+
+use std::path::PathBuf;
+
+use wpp::headless;
+
+/// The size of the input images.
+const SIZE: wgpu::Extent3d = wgpu::Extent3d {
+    width: 32,
+    height: 32,
+    depth_or_array_layers: 1,
+};
+
+/// The largest difference of a single channel which is not reported as an error.
+const CHANNEL_TOLERANCE: u8 = 2;
+
+/// The largest mean perceptual difference in range `0..=1` which is not reported as an error.
+const PERCEPTUAL_TOLERANCE: f32 = 0.001;
+
+/// The maximum of the YIQ color difference used by [perceptual_difference].
+const MAX_YIQ_DELTA: f32 = 35215.0;
+
+/// A deterministic input image.
+#[derive(Clone, Copy, Debug)]
+enum Input {
+    /// Red increases to the right, green to the bottom and blue along the diagonal.
+    Gradient,
+    /// Squares of saturated colors, black and white with hard edges.
+    Patches,
+}
+
+impl Input {
+    /// The name which is used for the reference images.
+    fn name(self) -> &'static str {
+        match self {
+            Input::Gradient => "gradient",
+            Input::Patches => "patches",
+        }
+    }
+
+    /// Generates the tightly packed RGBA8 pixels of the image.
+    fn pixels(self) -> Vec<u8> {
+        const PALETTE: [[u8; 3]; 8] = [
+            [255, 0, 0],
+            [0, 255, 0],
+            [0, 0, 255],
+            [255, 255, 0],
+            [0, 255, 255],
+            [255, 0, 255],
+            [255, 255, 255],
+            [0, 0, 0],
+        ];
+
+        let (width, height) = (SIZE.width, SIZE.height);
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                let [r, g, b] = match self {
+                    Input::Gradient => [
+                        x * 255 / (width - 1),
+                        y * 255 / (height - 1),
+                        (x + y) * 255 / (width + height - 2),
+                    ]
+                    .map(|c| c as u8),
+                    Input::Patches => PALETTE[((x / 8 + y / 8 * 3) % 8) as usize],
+                };
+                [r, g, b, 255]
+            })
+            .collect()
+    }
+}
+
+/// The result of comparing an image with its reference.
+#[derive(Debug)]
+struct Comparison {
+    /// The largest difference of a single channel.
+    max_channel_difference: u8,
+    /// The mean perceptual difference of all pixels in range `0..=1`.
+    mean_perceptual_difference: f32,
+    /// The number of pixels with at least one channel outside [CHANNEL_TOLERANCE].
+    failed_pixels: usize,
+    /// An RGBA8 image which marks the failed pixels in red on top of the faded reference.
+    diff: Vec<u8>,
+}
+
+impl Comparison {
+    /// Compares the RGBA8 images `actual` and `expected` of the same size.
+    fn new(actual: &[u8], expected: &[u8]) -> Comparison {
+        assert_eq!(actual.len(), expected.len(), "The image sizes differ");
+
+        let mut max_channel_difference = 0;
+        let mut perceptual_sum = 0.0;
+        let mut failed_pixels = 0;
+        let mut diff = Vec::with_capacity(actual.len());
+
+        for (a, e) in actual.chunks_exact(4).zip(expected.chunks_exact(4)) {
+            let channel_difference = a.iter().zip(e).map(|(a, e)| a.abs_diff(*e)).max();
+            let channel_difference = channel_difference.unwrap_or_default();
+            max_channel_difference = max_channel_difference.max(channel_difference);
+            perceptual_sum += perceptual_difference(a, e);
+
+            if channel_difference > CHANNEL_TOLERANCE {
+                failed_pixels += 1;
+                diff.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                let gray = 191 + (luma(e) / 4.0) as u8;
+                diff.extend_from_slice(&[gray, gray, gray, 255]);
+            }
+        }
+
+        Comparison {
+            max_channel_difference,
+            mean_perceptual_difference: perceptual_sum / (actual.len() / 4) as f32,
+            failed_pixels,
+            diff,
+        }
+    }
+
+    /// Returns whether the images are considered equal.
+    fn passed(&self) -> bool {
+        self.failed_pixels == 0 && self.mean_perceptual_difference <= PERCEPTUAL_TOLERANCE
+    }
+}
+
+/// Returns the luma of an RGBA8 pixel using the Rec. 601 weights.
+fn luma(pixel: &[u8]) -> f32 {
+    0.298_895_3 * f32::from(pixel[0])
+        + 0.586_622_5 * f32::from(pixel[1])
+        + 0.114_482_23 * f32::from(pixel[2])
+}
+
+/// Computes the perceptual difference of two RGBA8 pixels in range `0..=1`.
+///
+/// This uses the weighted YIQ color distance, which is also used by `pixelmatch`.
+/// See: http://www.progmat.uaem.mx:8080/artVol2Num2/Articulo3Vol2Num2.pdf.
+fn perceptual_difference(a: &[u8], b: &[u8]) -> f32 {
+    let i = |p: &[u8]| {
+        0.595_978 * f32::from(p[0]) - 0.274_176_1 * f32::from(p[1]) - 0.321_801_9 * f32::from(p[2])
+    };
+    let q = |p: &[u8]| {
+        0.211_470_17 * f32::from(p[0]) - 0.522_617_1 * f32::from(p[1])
+            + 0.311_146_94 * f32::from(p[2])
+    };
+
+    let y = luma(a) - luma(b);
+    let i = i(a) - i(b);
+    let q = q(a) - q(b);
+
+    (0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q) / MAX_YIQ_DELTA
+}
+
+/// Reads an RGBA8 PNG image.
+fn read_png(path: &PathBuf) -> Option<Vec<u8>> {
+    let decoder = png::Decoder::new(std::fs::File::open(path).ok()?);
+    let mut reader = decoder
+        .read_info()
+        .expect("Failed to read the reference image");
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut pixels)
+        .expect("Failed to decode the reference image");
+    assert_eq!(
+        (info.color_type, info.bit_depth),
+        (png::ColorType::Rgba, png::BitDepth::Eight),
+        "The reference image {} must be stored as RGBA8",
+        path.display()
+    );
+    assert_eq!(
+        (info.width, info.height),
+        (SIZE.width, SIZE.height),
+        "The reference image {} has the wrong size",
+        path.display()
+    );
+    pixels.truncate(info.buffer_size());
+
+    Some(pixels)
+}
+
+/// Writes an RGBA8 image of [SIZE] as PNG.
+fn write_png(path: &PathBuf, pixels: &[u8]) {
+    let file = std::fs::File::create(path).expect("Failed to create the image");
+    let mut encoder = png::Encoder::new(file, SIZE.width, SIZE.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(pixels))
+        .expect("Failed to write the image");
+}
+
+/// Renders every [Input] through the effect created by `create_effect` and compares the
+/// results with the reference images `tests/golden/<name>_<input>.png`.
+///
+/// The effect must use [wgpu::TextureFormat::Rgba8Unorm] as output format.
+/// The test is skipped if no adapter is available.
+fn check_golden<E: headless::HeadlessEffect>(
+    name: &str,
+    create_effect: impl Fn(&wgpu::Device, &wgpu::Queue) -> E,
+) {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+
+    let update = std::env::var_os("WPP_UPDATE_GOLDEN").is_some();
+    let reference_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let output_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+    std::fs::create_dir_all(&output_dir).expect("Failed to create the output directory");
+
+    let mut failures = Vec::new();
+    for input in [Input::Gradient, Input::Patches] {
+        let file_name = format!("{name}_{}.png", input.name());
+        let reference_path = reference_dir.join(&file_name);
+
+        let mut effect = create_effect(&device, &queue);
+        let actual = headless::process_rgba8(&device, &queue, &mut effect, &SIZE, &input.pixels())
+            .expect("Failed to read back the result");
+
+        if update {
+            write_png(&reference_path, &actual);
+            continue;
+        }
+
+        let actual_path = output_dir.join(format!("{name}_{}.actual.png", input.name()));
+        let Some(expected) = read_png(&reference_path) else {
+            write_png(&actual_path, &actual);
+            failures.push(format!(
+                "{file_name}: the reference image is missing, the result was written to {}",
+                actual_path.display()
+            ));
+            continue;
+        };
+
+        let comparison = Comparison::new(&actual, &expected);
+        if !comparison.passed() {
+            let diff_path = output_dir.join(format!("{name}_{}.diff.png", input.name()));
+            write_png(&actual_path, &actual);
+            write_png(&diff_path, &comparison.diff);
+            failures.push(format!(
+                "{file_name}: {} pixels differ by more than {CHANNEL_TOLERANCE}, \
+                 max channel difference {}, mean perceptual difference {:.5}, see {}",
+                comparison.failed_pixels,
+                comparison.max_channel_difference,
+                comparison.mean_perceptual_difference,
+                diff_path.display()
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn comparison_detects_differences() {
+    let expected = Input::Patches.pixels();
+    let mut actual = expected.clone();
+    assert!(Comparison::new(&actual, &expected).passed());
+
+    actual[1] += CHANNEL_TOLERANCE;
+    assert!(Comparison::new(&actual, &expected).passed());
+
+    actual[5] += CHANNEL_TOLERANCE + 1;
+    let comparison = Comparison::new(&actual, &expected);
+    assert!(!comparison.passed());
+    assert_eq!(comparison.failed_pixels, 1);
+    assert_eq!(&comparison.diff[4..8], &[255, 0, 0, 255]);
+}
+
+#[cfg(feature = "grayscale")]
+#[test]
+fn grayscale() {
+    use wpp::grayscale::GrayscaleEffect;
+
+    check_golden("grayscale", |device, _| {
+        GrayscaleEffect::new(device, &SIZE, wgpu::TextureFormat::Rgba8Unorm)
+    });
+
+    // The reference images themselves must contain the luma of the inputs.
+    let Some(reference) = read_png(&PathBuf::from(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/grayscale_gradient.png"
+    ))) else {
+        return;
+    };
+    for (input, output) in Input::Gradient
+        .pixels()
+        .chunks_exact(4)
+        .zip(reference.chunks_exact(4))
+    {
+        assert_eq!(output[0], output[1]);
+        assert_eq!(output[1], output[2]);
+        assert!((f32::from(output[0]) - luma(input)).abs() <= 1.0);
+    }
+}
@@ -0,0 +1,220 @@
+#![cfg(feature = "grayscale")]
+
+mod common;
+
+use wpp::{grayscale::GrayscaleEffect, headless, DynEffect, EffectChain, EffectFormat};
+
+#[test]
+fn grayscale_rgba8() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+
+    // Width of 3 pixels results in rows which must be padded for the readback.
+    let size = wgpu::Extent3d {
+        width: 3,
+        height: 2,
+        depth_or_array_layers: 1,
+    };
+    let pixels = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 255, 255],
+        [0, 0, 0, 255],
+        [128, 128, 128, 255],
+    ]
+    .concat();
+
+    let mut effect = GrayscaleEffect::new(&device, &size, wgpu::TextureFormat::Rgba8Unorm);
+    let result = headless::process_rgba8(&device, &queue, &mut effect, &size, &pixels).unwrap();
+
+    assert_eq!(result.len(), pixels.len());
+    for (input, output) in pixels.chunks_exact(4).zip(result.chunks_exact(4)) {
+        assert_eq!(output[0], output[1]);
+        assert_eq!(output[1], output[2]);
+
+        let luma =
+            0.299 * f32::from(input[0]) + 0.587 * f32::from(input[1]) + 0.114 * f32::from(input[2]);
+        assert!(
+            (f32::from(output[0]) - luma).abs() <= 1.0,
+            "{input:?} -> {output:?}"
+        );
+    }
+}
+
+#[test]
+fn grayscale_rgba16f() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+
+    let size = wgpu::Extent3d {
+        width: 2,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let pixels = [[4.0, 2.0, 1.0, 1.0], [0.0, 0.5, 0.0, 1.0]].concat();
+
+    let mut effect = GrayscaleEffect::new(&device, &size, wgpu::TextureFormat::Rgba16Float);
+    let result = headless::process_rgba16f(&device, &queue, &mut effect, &size, &pixels).unwrap();
+
+    assert_eq!(result.len(), pixels.len());
+    for (input, output) in pixels.chunks_exact(4).zip(result.chunks_exact(4)) {
+        let luma = 0.299 * input[0] + 0.587 * input[1] + 0.114 * input[2];
+        for value in &output[..3] {
+            assert!(
+                (value - luma).abs() <= luma * 0.002,
+                "{input:?} -> {output:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn disabled_effects_pass_the_input_through() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+
+    let size = wgpu::Extent3d {
+        width: 2,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let pixels = [[255, 0, 0, 255], [0, 64, 128, 255]].concat();
+
+    let mut effect = GrayscaleEffect::new(&device, &size, wgpu::TextureFormat::Rgba8Unorm);
+    effect.set_enabled(&device, false);
+    let result = headless::process_rgba8(&device, &queue, &mut effect, &size, &pixels).unwrap();
+
+    assert_eq!(result, pixels);
+}
+
+#[test]
+fn chain_copies_the_result_if_the_last_effect_is_disabled() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+
+    let size = wgpu::Extent3d {
+        width: 2,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let pixels = [[255, 0, 0, 255], [0, 0, 255, 255]].concat();
+
+    let effects: Vec<Box<dyn DynEffect>> = vec![
+        Box::new(GrayscaleEffect::new(&device, &size, format)),
+        Box::new(GrayscaleEffect::new(&device, &size, format)),
+    ];
+    let mut chain = EffectChain::new(&device, &size, format, effects);
+    chain.effects_mut()[1].set_enabled(&device, &queue, false);
+    let result = headless::process_rgba8(&device, &queue, &mut chain, &size, &pixels).unwrap();
+
+    // Only the first effect is applied, whose result is copied into the output.
+    assert_eq!(result[0], result[1]);
+    assert!((i32::from(result[0]) - 76).abs() <= 1, "{result:?}");
+    assert_eq!(result[4], result[6]);
+    assert!((i32::from(result[4]) - 29).abs() <= 1, "{result:?}");
+}
+
+#[test]
+fn chain_uses_a_separate_intermediate_format() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+
+    let size = wgpu::Extent3d {
+        width: 2,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let intermediate = wgpu::TextureFormat::Rgba16Float;
+    let output = wgpu::TextureFormat::Rgba8Unorm;
+    let pixels = [[255, 0, 0, 255], [0, 0, 255, 255]].concat();
+
+    // Only the last effect writes into the output format.
+    let effects: Vec<Box<dyn DynEffect>> = vec![
+        Box::new(GrayscaleEffect::new(&device, &size, intermediate)),
+        Box::new(GrayscaleEffect::new(&device, &size, output)),
+    ];
+    let format = EffectFormat::new(intermediate, output);
+    let mut chain = EffectChain::new(&device, &size, format, effects);
+
+    let result = headless::process_rgba8(&device, &queue, &mut chain, &size, &pixels).unwrap();
+    assert!((i32::from(result[0]) - 76).abs() <= 1, "{result:?}");
+
+    // The intermediate result is converted into the output format if the last effect is disabled.
+    chain.effects_mut()[1].set_enabled(&device, &queue, false);
+    let result = headless::process_rgba8(&device, &queue, &mut chain, &size, &pixels).unwrap();
+    assert!((i32::from(result[0]) - 76).abs() <= 1, "{result:?}");
+    assert!((i32::from(result[4]) - 29).abs() <= 1, "{result:?}");
+}
+
+#[cfg(feature = "tonemap")]
+#[test]
+fn tonemap_rgba16f_to_rgba8() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+
+    let size = wgpu::Extent3d {
+        width: 4,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let pixels = [
+        [0.0, 0.0, 0.0, 1.0],
+        [0.5, 0.5, 0.5, 1.0],
+        [4.0, 4.0, 4.0, 1.0],
+        [64.0, 64.0, 64.0, 1.0],
+    ]
+    .concat();
+
+    let mut effect =
+        wpp::tonemap::TonemapEffect::new(&device, &size, wgpu::TextureFormat::Rgba8Unorm);
+    let result =
+        headless::process_rgba16f_to_rgba8(&device, &queue, &mut effect, &size, &pixels).unwrap();
+
+    assert_eq!(result.len(), pixels.len());
+    let values: Vec<u8> = result.chunks_exact(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(values[0], 0, "{result:?}");
+    assert!(
+        values.windows(2).all(|pair| pair[0] < pair[1]),
+        "{result:?}"
+    );
+    assert!(values[3] >= 240, "{result:?}");
+}
+
+#[cfg(feature = "smaa")]
+#[test]
+fn smaa_depth_mode_falls_back_to_luma_without_depth() {
+    use wpp::smaa::{SmaaEdgeDetection, SmaaEffect, SmaaSettings};
+
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+
+    let size = wgpu::Extent3d {
+        width: 16,
+        height: 16,
+        depth_or_array_layers: 1,
+    };
+    // A staircase edge between black and white, which is aliased in every row.
+    let pixels: Vec<u8> = (0..size.height)
+        .flat_map(|y| (0..size.width).map(move |x| if x > y / 2 + 4 { 255 } else { 0 }))
+        .flat_map(|value| [value, value, value, 255])
+        .collect();
+
+    let settings = SmaaSettings {
+        edge_detection: SmaaEdgeDetection::Depth,
+        ..Default::default()
+    };
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let mut effect = SmaaEffect::with_settings(&device, &queue, &size, format, &settings);
+    let result = headless::process_rgba8(&device, &queue, &mut effect, &size, &pixels).unwrap();
+
+    assert_ne!(result, pixels);
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "grayscale")]
+
+mod common;
+
+use wpp::{grayscale::GrayscaleEffect, DynEffect, Effect, EffectChain};
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+const SIZE: wgpu::Extent3d = wgpu::Extent3d {
+    width: 16,
+    height: 16,
+    depth_or_array_layers: 1,
+};
+
+const MINIMIZED: wgpu::Extent3d = wgpu::Extent3d {
+    width: 0,
+    height: 0,
+    depth_or_array_layers: 1,
+};
+
+/// Creates a texture view which can be used as the output of a frame.
+fn output_view(device: &wgpu::Device) -> wgpu::TextureView {
+    device
+        .create_texture(&wgpu::TextureDescriptor {
+            size: SIZE,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            label: None,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// Returns whether a frame of `effect` renders directly into `output_view`.
+fn passes_through<E: Effect>(
+    effect: &mut E,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    output_view: &wgpu::TextureView,
+) -> bool
+where
+    for<'a> E::Frame<'a>: std::ops::Deref<Target = wgpu::TextureView>,
+{
+    let frame = effect.start_frame(device, queue, output_view);
+    frame.global_id() == output_view.global_id()
+}
+
+#[test]
+fn zero_size_suspends_and_restores_the_effect() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+    let output_view = output_view(&device);
+
+    let mut effect = GrayscaleEffect::new(&device, &SIZE, FORMAT);
+    assert!(!effect.is_suspended());
+    assert!(!passes_through(&mut effect, &device, &queue, &output_view));
+
+    Effect::resize(&mut effect, &device, &MINIMIZED);
+    assert!(effect.is_suspended());
+    assert!(effect.is_enabled());
+    assert!(passes_through(&mut effect, &device, &queue, &output_view));
+
+    // Resizing while suspended keeps the effect suspended.
+    Effect::resize(&mut effect, &device, &wgpu::Extent3d { height: 0, ..SIZE });
+    assert!(effect.is_suspended());
+
+    Effect::resize(&mut effect, &device, &SIZE);
+    assert!(!effect.is_suspended());
+    assert!(!passes_through(&mut effect, &device, &queue, &output_view));
+}
+
+#[test]
+fn effects_can_be_created_and_enabled_while_suspended() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+    let output_view = output_view(&device);
+
+    let mut effect = GrayscaleEffect::new(&device, &MINIMIZED, FORMAT);
+    assert!(effect.is_suspended());
+    assert!(passes_through(&mut effect, &device, &queue, &output_view));
+
+    effect.set_enabled(&device, false);
+    Effect::resize(&mut effect, &device, &SIZE);
+    assert!(!effect.is_suspended());
+    assert!(passes_through(&mut effect, &device, &queue, &output_view));
+
+    Effect::resize(&mut effect, &device, &MINIMIZED);
+    effect.set_enabled(&device, true);
+    assert!(effect.is_enabled());
+    assert!(passes_through(&mut effect, &device, &queue, &output_view));
+
+    Effect::resize(&mut effect, &device, &SIZE);
+    assert!(!passes_through(&mut effect, &device, &queue, &output_view));
+}
+
+#[test]
+fn zero_size_suspends_the_chain() {
+    let Some((device, queue)) = common::device() else {
+        return;
+    };
+    let output_view = output_view(&device);
+
+    let effects: Vec<Box<dyn DynEffect>> = vec![
+        Box::new(GrayscaleEffect::new(&device, &SIZE, FORMAT)),
+        Box::new(GrayscaleEffect::new(&device, &SIZE, FORMAT)),
+    ];
+    let mut chain = EffectChain::new(&device, &SIZE, FORMAT, effects);
+    assert!(!passes_through(&mut chain, &device, &queue, &output_view));
+
+    Effect::resize(&mut chain, &device, &MINIMIZED);
+    assert!(chain.is_suspended());
+    assert!(passes_through(&mut chain, &device, &queue, &output_view));
+
+    Effect::resize(&mut chain, &device, &SIZE);
+    assert!(!chain.is_suspended());
+    assert!(!passes_through(&mut chain, &device, &queue, &output_view));
+}